@@ -0,0 +1,68 @@
+//! Compare the Bessel I0 of the kaiser window with the power series loop it
+//! replaced.
+
+#[allow(dead_code)]
+#[path = "../src/math.rs"]
+mod math;
+
+// the unit tests of the module are not run here
+#[allow(dead_code, unused_imports)]
+#[path = "../src/kaiser.rs"]
+mod kaiser;
+
+fn main() {
+    divan::main();
+}
+
+/// The power series loop until the terms are below 1e-10.
+fn bessel_i0_loop(x: f64) -> f64 {
+    let mut y = 1.0;
+    let mut t = 1.0;
+    for k in 1..32 {
+        t *= (x / (2.0 * k as f64)).powi(2);
+        y += t;
+        if t < 1e-10 {
+            break;
+        }
+    }
+    y
+}
+
+/// The arguments of I0 in a table of 120dB, like `kaiser::fill_filter_table`.
+fn args() -> Vec<f64> {
+    let beta = kaiser::calc_kaiser_beta(120.0);
+    (0..1024)
+        .map(|i| {
+            let pos = i as f64 / 1024.0;
+            beta * (1.0 - pos * pos).sqrt()
+        })
+        .collect()
+}
+
+#[divan::bench(name = "0. bessel_i0 loop", sample_count = 1000)]
+fn loop_i0(bencher: divan::Bencher) {
+    let xs = args();
+    bencher.bench_local(|| {
+        for &x in xs.iter() {
+            divan::black_box(bessel_i0_loop(divan::black_box(x)));
+        }
+    })
+}
+
+#[divan::bench(name = "1. bessel_i0", sample_count = 1000)]
+fn bessel_i0(bencher: divan::Bencher) {
+    let xs = args();
+    bencher.bench_local(|| {
+        for &x in xs.iter() {
+            divan::black_box(kaiser::bessel_i0(divan::black_box(x)));
+        }
+    })
+}
+
+#[divan::bench(name = "2. filter table a120 q512", sample_count = 10)]
+fn table_a120(bencher: divan::Bencher) {
+    let order = kaiser::calc_order(2.0, 120.0, 0.1);
+    let beta = kaiser::calc_kaiser_beta(120.0);
+    let mut table = vec![0.0; (order * 512 / 2 + 1) as usize];
+    bencher.bench_local(|| kaiser::fill_filter_table(&mut table, 0, 512, order, beta, 0.95))
+}
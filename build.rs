@@ -0,0 +1,76 @@
+//! Generate the preset filter tables for the `presets` feature.
+//!
+//! The tables are generated by the same code as `sinc::Manager`, and written
+//! to `OUT_DIR` as little-endian `f64`. The build script gets the features as
+//! the crate does, so without `std` the float functions come from `libm` too,
+//! which is a build dependency only with `presets`.
+
+#[cfg(feature = "presets")]
+use std::env;
+#[cfg(feature = "presets")]
+use std::fmt::Write as _;
+#[cfg(feature = "presets")]
+use std::fs;
+#[cfg(feature = "presets")]
+use std::path::Path;
+
+#[cfg(feature = "presets")]
+#[allow(dead_code)]
+#[path = "src/math.rs"]
+mod math;
+
+#[cfg(feature = "presets")]
+#[allow(dead_code)]
+#[path = "src/kaiser.rs"]
+mod kaiser;
+
+/// Attenuation and quantify of the 16bit presets in README.
+#[cfg(feature = "presets")]
+const PRESETS: [(f64, u32); 3] = [(96.0, 128), (108.0, 256), (120.0, 512)];
+
+/// The transition band width passed to `sinc::Manager::new`.
+#[cfg(feature = "presets")]
+const TRANS_WIDTH: f64 = 0.1;
+
+/// 2/1 stands for all the upsampling ratios, which share the same table.
+#[cfg(feature = "presets")]
+const RATIOS: [(i64, i64); 3] = [(2, 1), (147, 160), (1, 2)];
+
+fn main() {
+    println!("cargo:rerun-if-changed=build.rs");
+    println!("cargo:rerun-if-changed=src/kaiser.rs");
+    println!("cargo:rerun-if-changed=src/math.rs");
+    #[cfg(feature = "presets")]
+    generate();
+}
+
+#[cfg(feature = "presets")]
+fn generate() {
+    let out_dir = env::var("OUT_DIR").unwrap();
+    let mut code = String::from("const PRESETS: &[Preset] = &[\n");
+    for (atten, quan) in PRESETS {
+        for (numer, denom) in RATIOS {
+            // the same as sinc::Manager::new
+            let ratio = numer as f64 / denom as f64;
+            let kaiser_beta = kaiser::calc_kaiser_beta(atten);
+            let order = kaiser::calc_order(ratio, atten, TRANS_WIDTH);
+            let cutoff = ratio.min(1.0) * (1.0 - 0.5 * TRANS_WIDTH);
+            let len = (order * quan / 2) as usize;
+            let mut table = vec![0.0; len + 1];
+            kaiser::fill_filter_table(&mut table[..len], 0, quan, order, kaiser_beta, cutoff);
+            let bytes: Vec<u8> = table.iter().flat_map(|v| v.to_le_bytes()).collect();
+            let name = format!("preset_{atten}_{quan}_{numer}_{denom}.bin");
+            fs::write(Path::new(&out_dir).join(&name), bytes).unwrap();
+            writeln!(
+                code,
+                "    Preset {{ quan: {quan}, order: {order}, kaiser_beta: {:#x}, cutoff: {:#x}, \
+                 table: include_bytes!(concat!(env!(\"OUT_DIR\"), \"/{name}\")) }},",
+                kaiser_beta.to_bits(),
+                cutoff.to_bits(),
+            )
+            .unwrap();
+        }
+    }
+    code.push_str("];\n");
+    fs::write(Path::new(&out_dir).join("presets.rs"), code).unwrap();
+}
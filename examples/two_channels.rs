@@ -1,6 +1,5 @@
 use std::f64::consts::PI;
 
-use hound;
 use simple_src::{sinc, Convert};
 
 const SOURCE_FILE: &str = "two_channels_44k.wav";
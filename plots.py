@@ -35,9 +35,28 @@ def impulse(filename, passband=False):
     fs, data = wavfile.read(filename)
     _spectrum(fs, data, filename, impulse='passband' if passband else '')
 
-def raw_impulse(filename, fs, passband=False):
-    data = np.fromfile(filename, np.float64)
-    _spectrum(fs, data, filename, impulse='passband' if passband else '')
+def table(filename, fs, passband=False):
+    """Plot the filter table exported as CSV, fs is the lower sample rate."""
+    data = np.loadtxt(filename, delimiter=',', skiprows=1)
+    quan = round(1 / data[1, 1])
+    coef = data[:, 2]
+    kernel = np.concatenate((coef[:0:-1], coef)) / quan
+    # keep the band below fs / 2 after zero padding
+    N = len(kernel) * 16
+    fft_data = abs(np.fft.rfft(kernel, N))
+    freqs = np.fft.rfftfreq(N, 1 / (fs * quan))
+    mask = freqs <= fs / 2
+    fft_dB = 20 * np.log10(np.maximum(fft_data[mask] / max(fft_data), 1e-12))
+    plt.figure(figsize=(6, 4))
+    ymin, ymax, ystep = (-3, 1, 0.5) if passband else (-200, 10, 20)
+    ax = plt.gca()
+    ax.set(xlabel='Frequency in kHz', ylabel='Magnitude in dB',
+           xlim=(0, fs / 2000), ylim=(ymin, ymax),
+           yticks=np.arange(ymin, ymax, ystep), facecolor='black')
+    ax.plot(freqs[mask] / 1000, fft_dB, color='white')
+    ax.grid()
+    plt.title(('Passband of ' if passband else 'Response of ') + filename)
+    plt.show()
 
 def spectrogram(filename):
     fs, data = wavfile.read(filename)
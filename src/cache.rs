@@ -0,0 +1,205 @@
+//! Process-wide cache of sinc filter tables
+//!
+//! Managers with the same quantify, order, kaiser beta and cutoff share one
+//! table. The cache is disabled until a capacity is set, the least recently
+//! used tables are evicted when it is full, and tables still used by managers
+//! are kept alive by them.
+//!
+//! ```
+//! use simple_src::{cache, sinc};
+//!
+//! cache::set_capacity(64 << 20);
+//! let a = sinc::Manager::new(2.0, 120.0, 512, 0.1).unwrap();
+//! let b = sinc::Manager::new(2.0, 120.0, 512, 0.1).unwrap();
+//! assert!(cache::stats().hits >= 1);
+//! ```
+
+use std::sync::{Arc, Mutex, MutexGuard};
+
+type Key = (u32, u32, u64, u64);
+
+/// Statistics of the cache.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    /// Number of the cached tables.
+    pub entries: usize,
+    /// Bytes of the cached tables.
+    pub bytes: usize,
+    /// Max bytes, 0 means disabled.
+    pub capacity: usize,
+    pub hits: u64,
+    pub misses: u64,
+}
+
+struct Cache {
+    // the most recently used at the end
+    entries: Vec<(Key, Arc<Vec<f64>>)>,
+    stats: Stats,
+}
+
+static CACHE: Mutex<Cache> = Mutex::new(Cache::with_capacity(0));
+
+#[inline]
+fn table_bytes(table: &[f64]) -> usize {
+    core::mem::size_of_val(table)
+}
+
+impl Cache {
+    const fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: Vec::new(),
+            stats: Stats {
+                entries: 0,
+                bytes: 0,
+                capacity,
+                hits: 0,
+                misses: 0,
+            },
+        }
+    }
+
+    fn set_capacity(&mut self, bytes: usize) {
+        self.stats.capacity = bytes;
+        self.evict(bytes);
+    }
+
+    fn evict(&mut self, capacity: usize) {
+        while self.stats.bytes > capacity {
+            let (_, table) = self.entries.remove(0);
+            self.stats.bytes -= table_bytes(&table);
+        }
+        self.stats.entries = self.entries.len();
+    }
+
+    fn get(&mut self, key: Key) -> Option<Arc<Vec<f64>>> {
+        if let Some(i) = self.entries.iter().position(|(k, _)| *k == key) {
+            let entry = self.entries.remove(i);
+            let table = entry.1.clone();
+            self.entries.push(entry);
+            self.stats.hits += 1;
+            Some(table)
+        } else {
+            self.stats.misses += 1;
+            None
+        }
+    }
+
+    fn insert(&mut self, key: Key, table: Arc<Vec<f64>>) {
+        let bytes = table_bytes(&table);
+        let capacity = self.stats.capacity;
+        if bytes <= capacity && !self.entries.iter().any(|(k, _)| *k == key) {
+            self.stats.bytes += bytes;
+            self.entries.push((key, table));
+            self.evict(capacity);
+        }
+    }
+}
+
+fn lock(cache: &Mutex<Cache>) -> MutexGuard<'_, Cache> {
+    // the cache is always consistent, ignore the poison
+    cache.lock().unwrap_or_else(|e| e.into_inner())
+}
+
+/// Set the max bytes of the cached tables, 0 disables the cache and clears it.
+pub fn set_capacity(bytes: usize) {
+    lock(&CACHE).set_capacity(bytes);
+}
+
+/// Remove all the cached tables, the statistics are kept.
+pub fn clear() {
+    lock(&CACHE).evict(0);
+}
+
+/// Get the statistics.
+pub fn stats() -> Stats {
+    lock(&CACHE).stats
+}
+
+/// Get the table from the cache or generate it, the lock is not held while
+/// generating.
+pub(crate) fn get_or_insert<F>(quan: u32, order: u32, beta: f64, cutoff: f64, f: F) -> Arc<Vec<f64>>
+where
+    F: FnOnce() -> Vec<f64>,
+{
+    let key = (quan, order, beta.to_bits(), cutoff.to_bits());
+    get_or_insert_in(&CACHE, key, f)
+}
+
+fn get_or_insert_in<F>(cache: &Mutex<Cache>, key: Key, f: F) -> Arc<Vec<f64>>
+where
+    F: FnOnce() -> Vec<f64>,
+{
+    {
+        let mut cache = lock(cache);
+        if cache.stats.capacity == 0 {
+            drop(cache);
+            return Arc::new(f());
+        }
+        if let Some(table) = cache.get(key) {
+            return table;
+        }
+    }
+    let table = Arc::new(f());
+    lock(cache).insert(key, table.clone());
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(order: u32) -> Key {
+        (1, order, 0, 0)
+    }
+
+    fn table(len: usize) -> Arc<Vec<f64>> {
+        Arc::new(vec![0.0; len])
+    }
+
+    #[test]
+    fn test_lru() {
+        let mut cache = Cache::with_capacity(0);
+        cache.insert(key(1), table(512));
+        assert_eq!(cache.stats.entries, 0);
+        cache.set_capacity(1024 * 8);
+        let a = table(512);
+        cache.insert(key(1), a.clone());
+        assert!(Arc::ptr_eq(&a, &cache.get(key(1)).unwrap()));
+        assert_eq!(cache.stats.bytes, 512 * 8);
+        cache.insert(key(2), table(512));
+        assert_eq!(cache.stats.entries, 2);
+        // 1 is used again, so 2 is the least recently used
+        cache.get(key(1));
+        cache.insert(key(3), table(256));
+        assert_eq!(cache.stats.entries, 2);
+        assert!(cache.get(key(2)).is_none());
+        assert!(cache.get(key(1)).is_some());
+        // too large to be cached
+        cache.insert(key(4), table(2048));
+        assert!(cache.get(key(4)).is_none());
+        assert_eq!(cache.stats.hits, 3);
+        assert_eq!(cache.stats.misses, 2);
+        cache.set_capacity(0);
+        assert_eq!(cache.stats.entries, 0);
+        assert_eq!(cache.stats.bytes, 0);
+    }
+
+    #[test]
+    fn test_disabled() {
+        let cache = Mutex::new(Cache::with_capacity(0));
+        let a = get_or_insert_in(&cache, key(2), || vec![0.0; 16]);
+        let b = get_or_insert_in(&cache, key(2), || vec![0.0; 16]);
+        assert!(!Arc::ptr_eq(&a, &b));
+        assert_eq!(lock(&cache).stats, Stats::default());
+    }
+
+    #[test]
+    fn test_shared() {
+        let cache = Mutex::new(Cache::with_capacity(1024));
+        let a = get_or_insert_in(&cache, key(2), || vec![0.0; 16]);
+        let b = get_or_insert_in(&cache, key(2), || unreachable!());
+        assert!(Arc::ptr_eq(&a, &b));
+        let stats = lock(&cache).stats;
+        assert_eq!((stats.hits, stats.misses), (1, 1));
+    }
+}
@@ -0,0 +1,313 @@
+//! Converter configuration with a text form
+//!
+//! A [Config] holds the parameters of a [linear] or [sinc] `Manager`, it can
+//! be parsed from and written as a spec like `sinc:atten=120,quan=512,tw=0.09`.
+//!
+//! ```
+//! use simple_src::{config::Config, sinc};
+//!
+//! let config: Config = "sinc:old_sr=44100,new_sr=48000,atten=120,quan=512,tw=0.09"
+//!     .parse()
+//!     .unwrap();
+//! let manager = sinc::Manager::from_config(&config).unwrap();
+//! let same = sinc::Manager::from_config(&manager.config()).unwrap();
+//! assert_eq!(manager.order(), same.order());
+//! ```
+//!
+//! The keys are written in this order, all of them are optional:
+//!
+//! | key       | value                 |
+//! | --------- | --------------------- |
+//! | ratio     | `147/160` or `0.5`    |
+//! | old_sr    | old sample rate       |
+//! | new_sr    | new sample rate       |
+//! | atten     | attenuation           |
+//! | quan      | quantify              |
+//! | tw        | transition band width |
+//! | order     | order of filter       |
+//! | beta      | beta of kaiser window |
+//! | cutoff    | cutoff of filter      |
+//! | pass_freq | pass band frequency   |
+//! | aa        | `true` or `false`     |
+//!
+//! With the `serde` feature, `Config` is serialized as the text form.
+
+use alloc::string::{String, ToString};
+use core::fmt;
+use core::str::FromStr;
+
+#[cfg(doc)]
+use crate::{linear, sinc};
+
+use super::{Error, Ratio, Result};
+
+/// Why a config is rejected.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The algorithm before `:` is unknown.
+    UnknownAlgorithm(String),
+    /// The key is unknown.
+    UnknownKey(String),
+    /// The value of the key cannot be parsed.
+    InvalidValue(&'static str),
+    /// The config is for another algorithm.
+    WrongAlgorithm,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownAlgorithm(name) => write!(f, "unknown algorithm `{name}`"),
+            Self::UnknownKey(key) => write!(f, "unknown key `{key}`"),
+            Self::InvalidValue(key) => write!(f, "invalid value of `{key}`"),
+            Self::WrongAlgorithm => write!(f, "config for another algorithm"),
+        }
+    }
+}
+
+impl core::error::Error for ConfigError {}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Algorithm {
+    Linear,
+    #[default]
+    Sinc,
+}
+
+impl Algorithm {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Linear => "linear",
+            Self::Sinc => "sinc",
+        }
+    }
+}
+
+/// The parameters of a `Manager`, which are passed to its `Builder`.
+#[derive(Clone, Debug, Default, PartialEq)]
+#[cfg_attr(
+    feature = "serde",
+    derive(serde::Serialize, serde::Deserialize),
+    serde(into = "String", try_from = "String")
+)]
+pub struct Config {
+    pub algorithm: Algorithm,
+    pub ratio: Option<Ratio>,
+    pub old_sr: Option<u32>,
+    pub new_sr: Option<u32>,
+    pub atten: Option<f64>,
+    pub quan: Option<u32>,
+    pub trans_width: Option<f64>,
+    pub order: Option<u32>,
+    pub kaiser_beta: Option<f64>,
+    pub cutoff: Option<f64>,
+    pub pass_freq: Option<u32>,
+    pub anti_alias: bool,
+}
+
+impl Config {
+    /// Create an empty `Config` of the algorithm.
+    pub fn new(algorithm: Algorithm) -> Self {
+        Self {
+            algorithm,
+            ..Default::default()
+        }
+    }
+
+    /// Check the algorithm before building a `Manager`.
+    pub(crate) fn expect(&self, algorithm: Algorithm) -> Result<()> {
+        if self.algorithm == algorithm {
+            Ok(())
+        } else {
+            Err(Error::InvalidConfig(ConfigError::WrongAlgorithm))
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(key: &'static str, value: &str) -> Result<Option<T>> {
+    match value.parse() {
+        Ok(v) => Ok(Some(v)),
+        Err(_) => Err(Error::InvalidConfig(ConfigError::InvalidValue(key))),
+    }
+}
+
+fn parse_ratio(value: &str) -> Result<Option<Ratio>> {
+    let invalid = || Error::InvalidConfig(ConfigError::InvalidValue("ratio"));
+    let ratio = match value.split_once('/') {
+        Some((numer, denom)) => {
+            let numer = numer.parse().map_err(|_| invalid())?;
+            let denom = denom.parse().map_err(|_| invalid())?;
+            Ratio::new(numer, denom)?
+        }
+        None => Ratio::try_from(value.parse::<f64>().map_err(|_| invalid())?)?,
+    };
+    Ok(Some(ratio))
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let s = s.trim();
+        let (name, params) = s.split_once(':').unwrap_or((s, ""));
+        let algorithm = match name.trim() {
+            "linear" => Algorithm::Linear,
+            "sinc" => Algorithm::Sinc,
+            other => {
+                let name = other.to_string();
+                return Err(Error::InvalidConfig(ConfigError::UnknownAlgorithm(name)));
+            }
+        };
+        let mut config = Config::new(algorithm);
+        for param in params.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let value = value.trim();
+            match key.trim() {
+                "ratio" => config.ratio = parse_ratio(value)?,
+                "old_sr" => config.old_sr = parse_value("old_sr", value)?,
+                "new_sr" => config.new_sr = parse_value("new_sr", value)?,
+                "atten" => config.atten = parse_value("atten", value)?,
+                "quan" => config.quan = parse_value("quan", value)?,
+                "tw" => config.trans_width = parse_value("tw", value)?,
+                "order" => config.order = parse_value("order", value)?,
+                "beta" => config.kaiser_beta = parse_value("beta", value)?,
+                "cutoff" => config.cutoff = parse_value("cutoff", value)?,
+                "pass_freq" => config.pass_freq = parse_value("pass_freq", value)?,
+                "aa" => config.anti_alias = parse_value("aa", value)?.unwrap_or_default(),
+                other => {
+                    let key = other.to_string();
+                    return Err(Error::InvalidConfig(ConfigError::UnknownKey(key)));
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl fmt::Display for Config {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.algorithm.name())?;
+        let mut sep = ':';
+        let mut write = |f: &mut fmt::Formatter<'_>, key: &str, value: &dyn fmt::Display| {
+            let result = write!(f, "{sep}{key}={value}");
+            sep = ',';
+            result
+        };
+        if let Some(ratio) = self.ratio {
+            write(
+                f,
+                "ratio",
+                &format_args!("{}/{}", ratio.numer(), ratio.denom()),
+            )?;
+        }
+        if let Some(old_sr) = self.old_sr {
+            write(f, "old_sr", &old_sr)?;
+        }
+        if let Some(new_sr) = self.new_sr {
+            write(f, "new_sr", &new_sr)?;
+        }
+        if let Some(atten) = self.atten {
+            write(f, "atten", &atten)?;
+        }
+        if let Some(quan) = self.quan {
+            write(f, "quan", &quan)?;
+        }
+        if let Some(trans_width) = self.trans_width {
+            write(f, "tw", &trans_width)?;
+        }
+        if let Some(order) = self.order {
+            write(f, "order", &order)?;
+        }
+        if let Some(kaiser_beta) = self.kaiser_beta {
+            write(f, "beta", &kaiser_beta)?;
+        }
+        if let Some(cutoff) = self.cutoff {
+            write(f, "cutoff", &cutoff)?;
+        }
+        if let Some(pass_freq) = self.pass_freq {
+            write(f, "pass_freq", &pass_freq)?;
+        }
+        if self.anti_alias {
+            write(f, "aa", &true)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<Config> for String {
+    fn from(value: Config) -> Self {
+        value.to_string()
+    }
+}
+
+impl TryFrom<String> for Config {
+    type Error = Error;
+
+    fn try_from(value: String) -> Result<Self> {
+        value.parse()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() {
+        let config: Config = "sinc:atten=120,quan=512,tw=0.09".parse().unwrap();
+        assert_eq!(config.algorithm, Algorithm::Sinc);
+        assert_eq!(config.atten, Some(120.0));
+        assert_eq!(config.quan, Some(512));
+        assert_eq!(config.trans_width, Some(0.09));
+        let config: Config = " linear : ratio = 160/147, aa = true ".parse().unwrap();
+        assert_eq!(config.algorithm, Algorithm::Linear);
+        assert_eq!(config.ratio, Some(Ratio::new(160, 147).unwrap()));
+        assert!(config.anti_alias);
+        assert_eq!(
+            "sinc".parse::<Config>().unwrap(),
+            Config::new(Algorithm::Sinc)
+        );
+    }
+
+    #[test]
+    fn test_parse_err() {
+        let err = |s: &str| match s.parse::<Config>() {
+            Err(Error::InvalidConfig(err)) => err,
+            _ => panic!("not a config error"),
+        };
+        assert_eq!(
+            err("cubic:ratio=2"),
+            ConfigError::UnknownAlgorithm("cubic".to_string())
+        );
+        assert_eq!(
+            err("sinc:width=2"),
+            ConfigError::UnknownKey("width".to_string())
+        );
+        assert_eq!(err("sinc:quan=-1"), ConfigError::InvalidValue("quan"));
+        assert_eq!(err("sinc:ratio=1/x"), ConfigError::InvalidValue("ratio"));
+        assert!(matches!(
+            "sinc:ratio=1/0".parse::<Config>(),
+            Err(Error::UnsupportedRatio(_))
+        ));
+        assert!(matches!(
+            "sinc:ratio=1/-9223372036854775808".parse::<Config>(),
+            Err(Error::UnsupportedRatio(crate::RatioError::NotPositive))
+        ));
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let specs = [
+            "sinc",
+            "linear:ratio=160/147,aa=true",
+            "sinc:old_sr=44100,new_sr=48000,atten=120,quan=512,pass_freq=20000",
+            "sinc:ratio=1/2,quan=32,order=64,beta=7.25,cutoff=0.45",
+            "sinc:ratio=3/2,atten=96,quan=128,tw=0.09",
+        ];
+        for spec in specs {
+            let config: Config = spec.parse().unwrap();
+            assert_eq!(config.to_string(), spec);
+            assert_eq!(config.to_string().parse::<Config>().unwrap(), config);
+        }
+    }
+}
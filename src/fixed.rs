@@ -0,0 +1,294 @@
+//! Fixed-point sinc converter
+//!
+//! For targets without a fast FPU. The filter table of a [sinc]
+//! `Manager` is converted to Q30 integers, the phase is accumulated in
+//! integers and the products are summed in a saturating `i64` accumulator.
+//! Where the taps fall in the table is calculated once for each phase, so
+//! there is no division when converting.
+//!
+//! The samples are Q31 `i32`, Q15 `i16` samples can be shifted left by 16 bits
+//! before conversion and right by 16 bits after.
+//!
+//! ```
+//! use simple_src::{fixed, sinc};
+//!
+//! let samples = vec![0, 1 << 30, 0, -(1 << 30)];
+//! let manager = sinc::Manager::new(2.0, 96.0, 128, 0.1).unwrap();
+//! let manager = fixed::Manager::new(&manager);
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+use crate::{math, sinc};
+
+/// Bits of the fraction of the coefficients.
+const COEF_BITS: u32 = 30;
+
+/// Bits dropped from each product before accumulation, leaving headroom for
+/// long filters.
+const HEADROOM_BITS: u32 = 8;
+
+/// Bits of the fraction between two entries of the table, the difference of
+/// two entries times the fraction fits in `i64`.
+const FRAC_BITS: u32 = 30;
+
+/// Where the taps of one phase fall in the table.
+#[derive(Clone, Copy)]
+struct Phase {
+    /// The last tap at the left of the center.
+    center: usize,
+    left: usize,
+    left_frac: i64,
+    right: usize,
+    right_frac: i64,
+}
+
+/// Split the table position `x / scale` into index and fraction.
+fn split(x: u64, scale: u64) -> (usize, i64) {
+    let frac = ((x % scale) << FRAC_BITS) / scale;
+    ((x / scale) as usize, frac as i64)
+}
+
+fn calc_phases(denom: u64, order: u64, quan: u64) -> Vec<Phase> {
+    // distances are in units of 1 / (2 * denom) to keep half orders exact
+    let scale = 2 * denom;
+    (0..denom)
+        .map(|pos| {
+            let center = 2 * pos + order * denom;
+            let rem = center % scale;
+            let (left, left_frac) = split(rem * quan, scale);
+            let (right, right_frac) = split((scale - rem) * quan, scale);
+            Phase {
+                center: (center / scale) as usize,
+                left,
+                left_frac,
+                right,
+                right_frac,
+            }
+        })
+        .collect()
+}
+
+enum State {
+    Normal,
+    Suspend,
+}
+
+pub struct Converter {
+    numer: u64,
+    denom: u64,
+    pos: u64,
+    quan: usize,
+    phases: Arc<Vec<Phase>>,
+    filter: Arc<Vec<i32>>,
+    buf: VecDeque<i32>,
+    state: State,
+}
+
+impl Converter {
+    /// Sum the products of `samples` and the taps from `index`, stepping away
+    /// from the center.
+    #[inline]
+    fn sum<'a, I>(&self, samples: I, mut index: usize, frac: i64) -> i64
+    where
+        I: Iterator<Item = &'a i32>,
+    {
+        let last = self.filter.len() - 1;
+        let mut acc = 0i64;
+        for &s in samples {
+            if index >= last {
+                break;
+            }
+            let h1 = self.filter[index] as i64;
+            let h2 = self.filter[index + 1] as i64;
+            let h = h1 + (((h2 - h1) * frac) >> FRAC_BITS);
+            acc = acc.saturating_add((s as i64 * h) >> HEADROOM_BITS);
+            index += self.quan;
+        }
+        acc
+    }
+
+    #[inline]
+    fn interpolate(&self) -> i32 {
+        let phase = &self.phases[self.pos as usize];
+        let left = self.buf.range(..=phase.center).rev();
+        let right = self.buf.range(phase.center + 1..);
+        let acc = self
+            .sum(left, phase.left, phase.left_frac)
+            .saturating_add(self.sum(right, phase.right, phase.right_frac));
+        let shift = COEF_BITS - HEADROOM_BITS;
+        let rounded = acc.saturating_add(1 << (shift - 1)) >> shift;
+        rounded.clamp(i32::MIN as i64, i32::MAX as i64) as i32
+    }
+
+    /// Get the next sample converted, return `None` until the input samples is
+    /// not enough.
+    ///
+    /// Note that the output can be continued after `None` returned.
+    #[inline]
+    pub fn next_sample<I>(&mut self, iter: &mut I) -> Option<i32>
+    where
+        I: Iterator<Item = i32>,
+    {
+        loop {
+            match self.state {
+                State::Normal => {
+                    while self.pos >= self.denom {
+                        self.pos -= self.denom;
+                        if let Some(s) = iter.next() {
+                            self.buf.pop_front();
+                            self.buf.push_back(s);
+                        } else {
+                            self.state = State::Suspend;
+                            return None;
+                        }
+                    }
+                    let interp = self.interpolate();
+                    self.pos += self.numer;
+                    return Some(interp);
+                }
+                State::Suspend => {
+                    if let Some(s) = iter.next() {
+                        self.buf.pop_front();
+                        self.buf.push_back(s);
+                        self.state = State::Normal;
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+
+    /// Process samples and return an iterator, can be called multiple times.
+    #[inline]
+    pub fn process<I>(&mut self, iter: I) -> ConvertIter<'_, I>
+    where
+        I: Iterator<Item = i32>,
+    {
+        ConvertIter { iter, cvtr: self }
+    }
+}
+
+pub struct ConvertIter<'a, I> {
+    iter: I,
+    cvtr: &'a mut Converter,
+}
+
+impl<I> Iterator for ConvertIter<'_, I>
+where
+    I: Iterator<Item = i32>,
+{
+    type Item = i32;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        self.cvtr.next_sample(&mut self.iter)
+    }
+}
+
+#[derive(Clone)]
+pub struct Manager {
+    numer: u64,
+    denom: u64,
+    order: u32,
+    quan: u32,
+    latency: usize,
+    phases: Arc<Vec<Phase>>,
+    filter: Arc<Vec<i32>>,
+}
+
+impl Manager {
+    /// Create a `Manager` with the parameters and the filter table of a
+    /// [sinc::Manager].
+    pub fn new(manager: &sinc::Manager) -> Self {
+        let one = (1i64 << COEF_BITS) as f64;
+        let filter = manager
+            .filter
+            .iter()
+            .map(|h| math::round(h * one) as i32)
+            .collect();
+        let step = manager.ratio.recip();
+        let denom = *step.denom() as u64;
+        let phases = calc_phases(denom, manager.order as u64, manager.quan as u64);
+        Self {
+            numer: *step.numer() as u64,
+            denom,
+            order: manager.order,
+            quan: manager.quan,
+            latency: manager.latency(),
+            phases: Arc::new(phases),
+            filter: Arc::new(filter),
+        }
+    }
+
+    /// Create a `Converter` which actually implement the interpolation.
+    #[inline]
+    pub fn converter(&self) -> Converter {
+        let taps = (self.order + 1) as usize;
+        let mut buf = VecDeque::with_capacity(taps);
+        buf.extend(core::iter::repeat_n(0, taps));
+        Converter {
+            numer: self.numer,
+            denom: self.denom,
+            pos: 0,
+            quan: self.quan as usize,
+            phases: self.phases.clone(),
+            filter: self.filter.clone(),
+            buf,
+            state: State::Normal,
+        }
+    }
+
+    /// Get the latency of the FIR filter.
+    #[inline]
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    /// Get the order of the FIR filter.
+    #[inline]
+    pub fn order(&self) -> u32 {
+        self.order
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Convert;
+
+    #[test]
+    fn test_match_float() {
+        let manager = sinc::Manager::new(1.5, 96.0, 128, 0.1).unwrap();
+        let fixed = Manager::new(&manager);
+        let input: Vec<f64> = (0..512).map(|i| 0.5 * (i as f64 * 0.2).sin()).collect();
+        let one = (1u64 << 31) as f64;
+        let output: Vec<f64> = manager.converter().process(input.iter().copied()).collect();
+        let fixed_output: Vec<i32> = fixed
+            .converter()
+            .process(input.iter().map(|s| (s * one) as i32))
+            .collect();
+        assert_eq!(output.len(), fixed_output.len());
+        for (s, f) in output.iter().zip(&fixed_output) {
+            assert!((s - *f as f64 / one).abs() < 1e-7);
+        }
+    }
+
+    #[test]
+    fn test_saturate() {
+        let manager = sinc::Manager::new(2.0, 96.0, 128, 0.1).unwrap();
+        let mut converter = Manager::new(&manager).converter();
+        // the overshoot of a full scale square wave is clipped
+        let square = (0..256).map(|i| if i / 8 % 2 == 0 { i32::MAX } else { i32::MIN });
+        let output: Vec<i32> = converter.process(square).collect();
+        assert!(output.contains(&i32::MAX));
+        assert!(output.contains(&i32::MIN));
+    }
+}
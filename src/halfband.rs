@@ -0,0 +1,229 @@
+//! Half-band converters for ratio 2 and 1/2
+//!
+//! Half of the taps of a half-band filter are zero, so the [Upsampler] and
+//! [Downsampler] skip them and use fixed coefficients instead of the
+//! interpolated table of [sinc].
+//!
+//! ```
+//! use simple_src::{halfband, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = halfband::Manager::new(96.0, 0.1).unwrap();
+//! let mut upsampler = manager.upsampler();
+//! let mut downsampler = manager.downsampler();
+//! let upsampled: Vec<f64> = upsampler.process(samples.into_iter()).collect();
+//! for s in downsampler.process(upsampled.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use crate::{kaiser, math, sinc};
+
+use super::{check_range, Convert, Result};
+
+/// Max number of the nonzero taps on each side.
+pub(crate) const MAX_TAPS: usize = 512;
+
+/// Calculate the number of nonzero taps on each side, the transition band
+/// width is relative to the Nyquist frequency of the lower sample rate.
+#[inline]
+pub(crate) fn calc_taps(atten: f64, trans_width: f64) -> usize {
+    let order = (atten - 8.0) / (2.285 * PI * trans_width * 0.5);
+    (math::ceil((order + 2.0) / 4.0) as usize).max(1)
+}
+
+pub struct Upsampler {
+    taps: Arc<Vec<f64>>,
+    buf: VecDeque<f64>,
+    odd: bool,
+}
+
+impl Upsampler {
+    /// Get the latency in output samples.
+    #[inline]
+    pub fn latency(&self) -> usize {
+        2 * self.taps.len()
+    }
+}
+
+impl Convert for Upsampler {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        let half = self.taps.len();
+        if self.odd {
+            let mut interp = 0.0;
+            for (i, h) in self.taps.iter().enumerate() {
+                interp += (self.buf[half - 1 - i] + self.buf[half + i]) * h;
+            }
+            self.odd = false;
+            Some(interp * 2.0)
+        } else {
+            let s = iter.next()?;
+            self.buf.pop_front();
+            self.buf.push_back(s);
+            self.odd = true;
+            Some(self.buf[half - 1])
+        }
+    }
+}
+
+pub struct Downsampler {
+    taps: Arc<Vec<f64>>,
+    buf: VecDeque<f64>,
+    pending: usize,
+}
+
+impl Downsampler {
+    /// Get the latency in output samples.
+    #[inline]
+    pub fn latency(&self) -> usize {
+        self.taps.len() - 1
+    }
+}
+
+impl Convert for Downsampler {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        while self.pending < 2 {
+            let s = iter.next()?;
+            self.buf.pop_front();
+            self.buf.push_back(s);
+            self.pending += 1;
+        }
+        self.pending = 0;
+        let center = 2 * self.taps.len() - 1;
+        let mut interp = self.buf[center] * 0.5;
+        for (i, h) in self.taps.iter().enumerate() {
+            let offset = 2 * i + 1;
+            interp += (self.buf[center - offset] + self.buf[center + offset]) * h;
+        }
+        Some(interp)
+    }
+}
+
+#[derive(Clone)]
+pub struct Manager {
+    taps: Arc<Vec<f64>>,
+}
+
+impl Manager {
+    /// Create a `Manager` with attenuation and transition band width.
+    ///
+    /// - atten: the attenuation in dB, support `[12.0, 180.0]`
+    /// - trans_width: the transition band width relative to the Nyquist
+    ///   frequency of the lower sample rate, centered on it, in `[0.01, 1.0]`
+    pub fn new(atten: f64, trans_width: f64) -> Result<Self> {
+        check_range("atten", atten, sinc::MIN_ATTEN, sinc::MAX_ATTEN)?;
+        check_range("trans_width", trans_width, 0.01, 1.0)?;
+        let count = calc_taps(atten, trans_width);
+        check_range("taps", count as u32, 1, MAX_TAPS as u32)?;
+        let beta = kaiser::calc_kaiser_beta(atten);
+        let order = 4 * count as u32;
+        let mut taps: Vec<f64> = (0..count)
+            .map(|i| {
+                let x = (2 * i + 1) as f64;
+                kaiser::sinc_c(x, 0.5) * kaiser::kaiser(x, order, beta)
+            })
+            .collect();
+        // keep the gain at DC exactly 1
+        let sum: f64 = taps.iter().sum();
+        taps.iter_mut().for_each(|h| *h *= 0.25 / sum);
+        Ok(Self {
+            taps: Arc::new(taps),
+        })
+    }
+
+    /// Create an `Upsampler` with ratio 2.
+    #[inline]
+    pub fn upsampler(&self) -> Upsampler {
+        let len = 2 * self.taps.len();
+        Upsampler {
+            taps: self.taps.clone(),
+            buf: VecDeque::from(vec![0.0; len]),
+            odd: false,
+        }
+    }
+
+    /// Create a `Downsampler` with ratio 1/2.
+    #[inline]
+    pub fn downsampler(&self) -> Downsampler {
+        let len = 4 * self.taps.len() - 1;
+        Downsampler {
+            taps: self.taps.clone(),
+            buf: VecDeque::from(vec![0.0; len]),
+            pending: 0,
+        }
+    }
+
+    /// Latency of the `Upsampler` in output samples.
+    #[inline]
+    pub(crate) fn up_latency(&self) -> usize {
+        2 * self.taps.len()
+    }
+
+    /// Latency of the `Downsampler` in output samples.
+    #[inline]
+    pub(crate) fn down_latency(&self) -> usize {
+        self.taps.len() - 1
+    }
+
+    /// Get the order of the FIR filter at the higher sample rate.
+    #[inline]
+    pub fn order(&self) -> u32 {
+        4 * self.taps.len() as u32 - 2
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manager_new() {
+        assert!(Manager::new(96.0, 0.1).is_ok());
+        assert!(Manager::new(11.9, 0.1).is_err());
+        assert!(Manager::new(96.0, 0.0).is_err());
+        assert!(Manager::new(96.0, 1.1).is_err());
+        assert!(Manager::new(180.0, 0.01).is_err());
+    }
+
+    #[test]
+    fn test_latency() {
+        let manager = Manager::new(96.0, 0.2).unwrap();
+        let impulse = (0..256).map(|i| if i == 64 { 1.0 } else { 0.0 });
+        let mut upsampler = manager.upsampler();
+        let output: Vec<f64> = upsampler.process(impulse.clone()).collect();
+        assert_eq!(output.len(), 512);
+        assert_eq!(output[upsampler.latency() + 128], 1.0);
+        let mut downsampler = manager.downsampler();
+        let output: Vec<f64> = downsampler.process(impulse).collect();
+        assert_eq!(output.len(), 128);
+        assert_eq!(output[downsampler.latency() + 32], 0.5);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let manager = Manager::new(120.0, 0.2).unwrap();
+        let mut upsampler = manager.upsampler();
+        let mut downsampler = manager.downsampler();
+        let latency = (upsampler.latency() + downsampler.latency() * 2) / 2;
+        let input: Vec<f64> = (0..1024).map(|i| (i as f64 * 0.3).sin()).collect();
+        let upsampled: Vec<f64> = upsampler.process(input.iter().copied()).collect();
+        let output: Vec<f64> = downsampler.process(upsampled.into_iter()).collect();
+        for i in 256..512 {
+            assert!((output[i + latency] - input[i]).abs() < 1e-5);
+        }
+    }
+}
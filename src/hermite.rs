@@ -0,0 +1,62 @@
+//! Hermite interpolation converter
+//!
+//! 4-point, 3rd-order Hermite (Catmull-Rom) interpolation, better than
+//! [linear](crate::linear) at a small cost.
+//!
+//! ```
+//! use simple_src::{hermite, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = hermite::Manager::new(2.0).unwrap();
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use crate::poly;
+
+/// The 4-point Hermite (Catmull-Rom) kernel.
+#[derive(Clone, Copy, Debug)]
+pub struct Hermite;
+
+impl poly::Kernel<4> for Hermite {
+    #[inline]
+    fn weights(x: f64) -> [f64; 4] {
+        let x2 = x * x;
+        let x3 = x2 * x;
+        [
+            0.5 * (-x3 + 2.0 * x2 - x),
+            0.5 * (3.0 * x3 - 5.0 * x2 + 2.0),
+            0.5 * (-3.0 * x3 + 4.0 * x2 + x),
+            0.5 * (x3 - x2),
+        ]
+    }
+}
+
+pub type Converter = poly::Converter<4>;
+
+pub type Manager = poly::Manager<Hermite, 4>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Convert;
+
+    #[test]
+    fn test_polynomial() {
+        // the interpolation is exact for polynomials of degree 2
+        let poly = |t: f64| 3.0 * t * t + 2.0 * t + 1.0;
+        let samples: Vec<f64> = (0..64).map(|i| poly(i as f64)).collect();
+        let mut converter = Manager::new(1.5).unwrap().converter();
+        for (k, s) in converter
+            .process(samples.into_iter())
+            .enumerate()
+            .skip(8)
+            .take(64)
+        {
+            let t = -2.0 + (k + 1) as f64 / 1.5;
+            assert!((s - poly(t)).abs() < 1e-6 * poly(t).abs().max(1.0));
+        }
+    }
+}
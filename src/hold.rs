@@ -0,0 +1,222 @@
+//! Zero-order hold and nearest-neighbour converter
+//!
+//! For control signals and step-like data, which should not be smeared by
+//! interpolation. Timing is the same as [linear](crate::linear).
+//!
+//! ```
+//! use simple_src::{hold, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = hold::Manager::with_mode(2.0, hold::Mode::Nearest).unwrap();
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use num_rational::Rational64;
+
+use crate::ratio::{check_supported, Ratio};
+
+use super::{Convert, Error, Result};
+
+/// Which input sample is picked for each output sample.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Mode {
+    /// Repeat the previous input sample.
+    #[default]
+    Hold,
+    /// Pick the nearest input sample, the previous one at the midpoint.
+    Nearest,
+}
+
+enum State {
+    First,
+    Normal,
+    Suspend,
+}
+
+pub struct Converter {
+    numer: usize,
+    denom: usize,
+    pos: usize,
+    // positions at or after this pick the next sample
+    threshold: usize,
+    last_in: [f64; 2],
+    state: State,
+}
+
+impl Converter {
+    #[inline]
+    fn new(step: Rational64, mode: Mode) -> Self {
+        let numer = *step.numer() as usize;
+        let denom = *step.denom() as usize;
+        let threshold = match mode {
+            Mode::Hold => denom,
+            Mode::Nearest => denom / 2 + 1,
+        };
+        Self {
+            numer,
+            denom,
+            pos: 0,
+            threshold,
+            last_in: [0.0; 2],
+            state: State::First,
+        }
+    }
+}
+
+impl Convert for Converter {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        loop {
+            match self.state {
+                State::First => {
+                    if let Some(s) = iter.next() {
+                        self.last_in[1] = s;
+                        self.pos = self.numer;
+                        self.state = State::Normal;
+                    } else {
+                        return None;
+                    }
+                }
+                State::Normal => {
+                    while self.pos >= self.denom {
+                        self.pos -= self.denom;
+                        self.last_in[0] = self.last_in[1];
+                        if let Some(s) = iter.next() {
+                            self.last_in[1] = s;
+                        } else {
+                            self.state = State::Suspend;
+                            return None;
+                        }
+                    }
+                    let picked = if self.pos >= self.threshold {
+                        self.last_in[1]
+                    } else {
+                        self.last_in[0]
+                    };
+                    self.pos += self.numer;
+                    return Some(picked);
+                }
+                State::Suspend => {
+                    if let Some(s) = iter.next() {
+                        self.last_in[1] = s;
+                        self.state = State::Normal;
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct Manager {
+    ratio: Rational64,
+    mode: Mode,
+}
+
+impl Manager {
+    /// Create a `Manager` in [Mode::Hold].
+    #[inline]
+    pub fn new<R>(ratio: R) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::with_mode(ratio, Mode::Hold)
+    }
+
+    #[inline]
+    pub fn with_mode<R>(ratio: R, mode: Mode) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        let ratio = ratio.try_into()?.into();
+        check_supported(ratio)?;
+        Ok(Self { ratio, mode })
+    }
+
+    #[inline]
+    pub fn mode(&self) -> Mode {
+        self.mode
+    }
+
+    #[inline]
+    pub fn converter(&self) -> Converter {
+        Converter::new(self.ratio.recip(), self.mode)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manager_ok() {
+        let ratio_ok = vec![0.0625, 0.063, 1.0, 15.9, 16.0];
+        for ratio in ratio_ok {
+            assert!(Manager::new(ratio).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_manager_err() {
+        let ratio_err = vec![
+            -1.0,
+            0.0,
+            0.0624,
+            16.01,
+            0.123456,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NAN,
+        ];
+        for ratio in ratio_err {
+            assert!(Manager::new(ratio).is_err());
+        }
+    }
+
+    #[test]
+    fn test_hold() {
+        let samples = [1.0, 2.0, 3.0];
+        let mut converter = Manager::new(2.0).unwrap().converter();
+        let output: Vec<f64> = converter.process(samples.into_iter()).collect();
+        assert_eq!(output, [0.0, 1.0, 1.0, 2.0, 2.0]);
+        let mut converter = Manager::new(0.5).unwrap().converter();
+        let output: Vec<f64> = converter
+            .process([1.0, 2.0, 3.0, 4.0, 5.0].into_iter())
+            .collect();
+        assert_eq!(output, [2.0, 4.0]);
+    }
+
+    #[test]
+    fn test_nearest() {
+        let manager = Manager::with_mode(4.0, Mode::Nearest).unwrap();
+        let mut converter = manager.converter();
+        let output: Vec<f64> = converter.process([1.0, 2.0].into_iter()).collect();
+        assert_eq!(output, [0.0, 0.0, 1.0, 1.0, 1.0, 1.0, 2.0]);
+    }
+
+    #[test]
+    fn test_streaming() {
+        let samples: Vec<f64> = (0..100).map(|i| i as f64).collect();
+        let manager = Manager::with_mode(1.5, Mode::Nearest).unwrap();
+        let whole: Vec<f64> = manager
+            .converter()
+            .process(samples.iter().copied())
+            .collect();
+        let mut converter = manager.converter();
+        let mut chunked = Vec::new();
+        for chunk in samples.chunks(7) {
+            chunked.extend(converter.process(chunk.iter().copied()));
+        }
+        assert_eq!(whole, chunked);
+    }
+}
@@ -0,0 +1,130 @@
+//! Kaiser window and windowed sinc
+//!
+//! Shared by the converters and the build script, which generates the preset
+//! tables with the same code, so only `core` and `crate::math` are used here.
+
+use core::f64::consts::PI;
+
+use crate::math;
+
+#[inline]
+pub(crate) fn sinc_c(x: f64, cutoff: f64) -> f64 {
+    if x != 0.0 {
+        math::sin(PI * x * cutoff) / (PI * x)
+    } else {
+        cutoff
+    }
+}
+
+/// Number of the terms of the power series of I0, enough for `I0_MAX`.
+const I0_TERMS: usize = 36;
+
+/// The max `x` of I0, which is the max kaiser beta.
+const I0_MAX: f64 = 20.0;
+
+/// Coefficients `1 / (k!)^2` of the power series of I0 in `(x / 2)^2`.
+const I0_COEFS: [f64; I0_TERMS] = {
+    let mut coefs = [1.0; I0_TERMS];
+    let mut k = 1;
+    while k < I0_TERMS {
+        coefs[k] = coefs[k - 1] / (k * k) as f64;
+        k += 1;
+    }
+    coefs
+};
+
+/// Modified Bessel function of the first kind of order 0 for `|x| <= 20`,
+/// the relative error is about 1e-15.
+#[inline]
+pub(crate) fn bessel_i0(x: f64) -> f64 {
+    debug_assert!(math::abs(x) <= I0_MAX);
+    let q = 0.25 * x * x;
+    let q2 = q * q;
+    let q4 = q2 * q2;
+    // 4 independent Horner chains in q^4, shorter than one chain in q
+    let mut p = [0.0; 4];
+    for coefs in I0_COEFS.chunks_exact(4).rev() {
+        for (p, c) in p.iter_mut().zip(coefs) {
+            *p = *p * q4 + c;
+        }
+    }
+    (p[0] + q * p[1]) + q2 * (p[2] + q * p[3])
+}
+
+#[inline]
+pub(crate) fn kaiser(x: f64, order: u32, beta: f64) -> f64 {
+    let half = order as f64 * 0.5;
+    if (x < -half) || (x > half) {
+        return 0.0;
+    }
+    bessel_i0(beta * math::sqrt(1.0 - math::powi(x / half, 2))) / bessel_i0(beta)
+}
+
+/// Fill `table` with the entries from `start`.
+#[inline]
+pub(crate) fn fill_filter_table(
+    table: &mut [f64],
+    start: usize,
+    quan: u32,
+    order: u32,
+    beta: f64,
+    cutoff: f64,
+) {
+    let i0_beta = bessel_i0(beta);
+    let half_order = order as f64 * 0.5;
+    for (i, coef) in table.iter_mut().enumerate() {
+        let pos = (start + i) as f64 / quan as f64;
+        let i0_1 = bessel_i0(beta * math::sqrt(1.0 - math::powi(pos / half_order, 2)));
+        *coef = sinc_c(pos, cutoff) * (i0_1 / i0_beta);
+    }
+}
+
+#[inline]
+pub(crate) fn calc_kaiser_beta(atten: f64) -> f64 {
+    if atten > 50.0 {
+        0.1102 * (atten - 8.7)
+    } else if atten >= 21.0 {
+        0.5842 * math::powf(atten - 21.0, 0.4) + 0.07886 * (atten - 21.0)
+    } else {
+        0.0
+    }
+}
+
+#[inline]
+pub(crate) fn calc_trans_width(ratio: f64, atten: f64, order: u32) -> f64 {
+    (atten - 8.0) / (2.285 * order as f64 * PI * ratio.min(1.0))
+}
+
+#[inline]
+pub(crate) fn calc_order(ratio: f64, atten: f64, trans_width: f64) -> u32 {
+    math::ceil((atten - 8.0) / (2.285 * trans_width * PI * ratio.min(1.0))) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the power series until the terms are negligible
+    fn bessel_i0_series(x: f64) -> f64 {
+        let mut y = 1.0;
+        let mut t = 1.0;
+        let mut k = 1.0;
+        while t > y * 1e-18 {
+            t *= (x / (2.0 * k)).powi(2);
+            y += t;
+            k += 1.0;
+        }
+        y
+    }
+
+    #[test]
+    fn test_bessel_i0() {
+        for i in 0..=2000 {
+            let x = i as f64 * 0.01;
+            let expected = bessel_i0_series(x);
+            let error = (bessel_i0(x) - expected).abs() / expected;
+            assert!(error < 1e-14, "x = {x}, error = {error}");
+        }
+        assert_eq!(bessel_i0(-3.0), bessel_i0(3.0));
+    }
+}
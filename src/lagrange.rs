@@ -0,0 +1,67 @@
+//! Lagrange interpolation converter
+//!
+//! 6-point, 5th-order Lagrange interpolation.
+//!
+//! ```
+//! use simple_src::{lagrange, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = lagrange::Manager::new(2.0).unwrap();
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use crate::poly;
+
+/// The 6-point Lagrange kernel.
+#[derive(Clone, Copy, Debug)]
+pub struct Lagrange;
+
+impl poly::Kernel<6> for Lagrange {
+    #[inline]
+    fn weights(x: f64) -> [f64; 6] {
+        let a = x + 2.0;
+        let b = x + 1.0;
+        let c = x;
+        let d = x - 1.0;
+        let e = x - 2.0;
+        let f = x - 3.0;
+        [
+            -b * c * d * e * f / 120.0,
+            a * c * d * e * f / 24.0,
+            -a * b * d * e * f / 12.0,
+            a * b * c * e * f / 12.0,
+            -a * b * c * d * f / 24.0,
+            a * b * c * d * e / 120.0,
+        ]
+    }
+}
+
+pub type Converter = poly::Converter<6>;
+
+pub type Manager = poly::Manager<Lagrange, 6>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Convert;
+
+    #[test]
+    fn test_polynomial() {
+        // the interpolation is exact for polynomials of degree 5
+        let poly = |t: f64| t.powi(5) - 4.0 * t.powi(3) + 2.0 * t + 1.0;
+        let samples: Vec<f64> = (0..64).map(|i| poly(i as f64)).collect();
+        let mut converter = Manager::new(1.5).unwrap().converter();
+        for (k, s) in converter
+            .process(samples.into_iter())
+            .enumerate()
+            .skip(8)
+            .take(64)
+        {
+            let t = -3.0 + (k + 1) as f64 / 1.5;
+            assert!((s - poly(t)).abs() < 1e-6 * poly(t).abs().max(1.0));
+        }
+    }
+}
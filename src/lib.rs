@@ -2,10 +2,49 @@
 //!
 //! ## Usage
 //!
-//! See [sinc] or [linear]
+//! See [sinc] or [linear], [hermite], [lagrange] and [optimal] are between
+//! them in quality and cost and built on [poly], [multistage] is for large
+//! ratios and [hold] is for step-like control signals. [fixed] runs the sinc
+//! filter in fixed-point for targets without FPU.
+//!
+//! ## Features
+//!
+//! - `std`: enabled by default, without it the crate is `no_std` and needs
+//!   `alloc`, the float functions are provided by `libm`.
+//! - `parallel`: generate large sinc filter tables with multiple threads.
+//! - `presets`: embed the sinc filter tables of the 16bit presets, which are
+//!   generated at build time, see [sinc].
+
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
 
+mod math;
+
+#[cfg(feature = "std")]
+pub mod cache;
+pub mod config;
+pub mod fixed;
+pub mod halfband;
+pub mod hermite;
+pub mod hold;
+mod kaiser;
+pub mod lagrange;
 pub mod linear;
+pub mod multistage;
+pub mod optimal;
+pub mod oversample;
+pub mod poly;
+#[cfg(feature = "presets")]
+mod presets;
+pub mod ratio;
 pub mod sinc;
+pub mod table;
+pub mod true_peak;
+
+use config::ConfigError;
+pub use ratio::{Ratio, RatioError};
+use table::TableError;
 
 pub struct ConvertIter<'a, I, C> {
     iter: I,
@@ -52,22 +91,114 @@ pub trait Convert {
     }
 }
 
-#[derive(Debug)]
+#[derive(Clone, Debug, PartialEq)]
 pub enum Error {
-    UnsupportedRatio,
-    InvalidParam,
+    /// The ratio is rejected for the reason.
+    UnsupportedRatio(RatioError),
+    /// The parameter `name` is `value`, which is not in `[min, max]`.
+    OutOfRange {
+        name: &'static str,
+        value: f64,
+        min: f64,
+        max: f64,
+    },
+    /// The parameter `name` is not one of the supported values.
+    InvalidParam { name: &'static str, value: f64 },
+    /// The parameters set to the builder are not enough.
     NotEnoughParam,
+    /// The config cannot be parsed or used.
+    InvalidConfig(ConfigError),
+    /// The snapshot is taken from a converter with other parameters.
+    SnapshotMismatch,
+    /// The filter table cannot be imported.
+    InvalidTable(TableError),
+    /// The filter table of `bytes` is larger than `max`.
+    TableTooLarge { bytes: usize, max: usize },
+    /// The multiply-adds per output sample are more than `max`.
+    CostTooHigh { mul_adds: f64, max: f64 },
+    /// The latency in output samples is more than `max`.
+    LatencyTooHigh { latency: usize, max: usize },
+    /// The parameter `name` is set but ignored by a strict builder.
+    IgnoredParam(&'static str),
+}
+
+impl core::fmt::Display for Error {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::UnsupportedRatio(reason) => write!(f, "unsupported ratio: {reason}"),
+            Self::OutOfRange {
+                name,
+                value,
+                min,
+                max,
+            } => write!(f, "{name} = {value} is out of range [{min}, {max}]"),
+            Self::InvalidParam { name, value } => write!(f, "{name} = {value} is not supported"),
+            Self::NotEnoughParam => write!(f, "not enough parameters"),
+            Self::InvalidConfig(reason) => write!(f, "invalid config: {reason}"),
+            Self::SnapshotMismatch => write!(f, "snapshot of another converter"),
+            Self::InvalidTable(reason) => write!(f, "invalid table: {reason}"),
+            Self::TableTooLarge { bytes, max } => {
+                write!(f, "table of {bytes} bytes is larger than {max}")
+            }
+            Self::CostTooHigh { mul_adds, max } => {
+                write!(f, "{mul_adds} multiply-adds per sample is more than {max}")
+            }
+            Self::LatencyTooHigh { latency, max } => {
+                write!(f, "latency of {latency} samples is more than {max}")
+            }
+            Self::IgnoredParam(name) => write!(f, "parameter `{name}` is ignored"),
+        }
+    }
+}
+
+impl core::error::Error for Error {
+    fn source(&self) -> Option<&(dyn core::error::Error + 'static)> {
+        match self {
+            Self::UnsupportedRatio(reason) => Some(reason),
+            Self::InvalidConfig(reason) => Some(reason),
+            Self::InvalidTable(reason) => Some(reason),
+            _ => None,
+        }
+    }
 }
 
-pub type Result<T> = std::result::Result<T, Error>;
+impl From<core::convert::Infallible> for Error {
+    fn from(value: core::convert::Infallible) -> Self {
+        match value {}
+    }
+}
+
+pub type Result<T> = core::result::Result<T, Error>;
 
-use num_rational::Rational64;
+/// Estimated cost of a converter, see `Manager::cost`.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Cost {
+    /// Multiply-adds per output sample.
+    pub mul_adds: f64,
+    /// Filter table lookups per output sample.
+    pub lookups: f64,
+    /// Input samples kept as history.
+    pub history: usize,
+    /// Bytes of the filter table and one converter.
+    pub memory: usize,
+}
 
-fn supported_ratio(ratio: Rational64) -> bool {
-    ratio > Rational64::default()
-        && *ratio.numer() <= 1024
-        && ratio.ceil().to_integer() <= 16
-        && ratio.recip().ceil().to_integer() <= 16
+/// Check if `value` of the parameter `name` is in `[min, max]`.
+#[inline]
+fn check_range<T>(name: &'static str, value: T, min: T, max: T) -> Result<()>
+where
+    T: Copy + PartialOrd + Into<f64>,
+{
+    if (min..=max).contains(&value) {
+        Ok(())
+    } else {
+        Err(Error::OutOfRange {
+            name,
+            value: value.into(),
+            min: min.into(),
+            max: max.into(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -78,7 +209,7 @@ mod tests {
     struct DynTest;
 
     impl DynTest {
-        #[allow(dead_code)]
+        #[allow(dead_code, clippy::new_ret_no_self)]
         pub fn new(a: i32) -> Box<dyn Convert> {
             if a == 0 {
                 let manager = linear::Manager::new(2.0).unwrap();
@@ -90,6 +221,25 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_error() {
+        let err = sinc::Manager::new(2.0, 72.0, 0, 0.1).err().unwrap();
+        assert_eq!(
+            err,
+            Error::OutOfRange {
+                name: "quan",
+                value: 0.0,
+                min: 1.0,
+                max: 16384.0
+            }
+        );
+        assert_eq!(err.to_string(), "quan = 0 is out of range [1, 16384]");
+        let err = linear::Manager::new(20.0).err().unwrap();
+        assert_eq!(err.to_string(), "unsupported ratio: greater than 16");
+        let err: Box<dyn std::error::Error> = Box::new(err);
+        assert!(err.source().is_some());
+    }
+
     #[test]
     #[ignore = "display only"]
     fn test1() {
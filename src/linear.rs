@@ -10,46 +10,187 @@
 //!     println!("{s}");
 //! }
 //! ```
+//!
+//! Downsampling without a lowpass aliases heavily, [Manager::with_anti_alias]
+//! adds a 6th-order Butterworth IIR pre-filter when ratio < 1, which keeps the
+//! latency small.
+
+use alloc::vec::Vec;
+use core::f64::consts::{FRAC_1_SQRT_2, PI};
 
 use num_rational::Rational64;
 
-use crate::supported_ratio;
+use crate::config::{Algorithm, Config};
+use crate::math;
+use crate::ratio::{check_supported, Ratio};
+
+use super::{Convert, Cost, Error, Result};
+
+/// Q of the biquad sections of a 6th-order Butterworth filter.
+const BUTTERWORTH_Q: [f64; 3] = [0.5176380902050415, FRAC_1_SQRT_2, 1.9318516525781366];
 
-use super::{Convert, Error, Result};
+/// Cutoff of the pre-filter relative to the output Nyquist frequency.
+const CUTOFF: f64 = 0.9;
 
+/// Transposed direct form II lowpass biquad.
+struct Biquad {
+    b0: f64,
+    b1: f64,
+    b2: f64,
+    a1: f64,
+    a2: f64,
+    z1: f64,
+    z2: f64,
+}
+
+impl Biquad {
+    /// Lowpass with cutoff `freq` relative to the sample rate.
+    fn lowpass(freq: f64, q: f64) -> Self {
+        let w0 = 2.0 * PI * freq;
+        let cos = math::cos(w0);
+        let alpha = math::sin(w0) / (2.0 * q);
+        let a0 = 1.0 + alpha;
+        let b1 = (1.0 - cos) / a0;
+        Self {
+            b0: 0.5 * b1,
+            b1,
+            b2: 0.5 * b1,
+            a1: -2.0 * cos / a0,
+            a2: (1.0 - alpha) / a0,
+            z1: 0.0,
+            z2: 0.0,
+        }
+    }
+
+    /// Group delay at DC in samples.
+    fn dc_delay(&self) -> f64 {
+        // 1 for the numerator, which is symmetric
+        1.0 - (self.a1 + 2.0 * self.a2) / (1.0 + self.a1 + self.a2)
+    }
+
+    #[inline]
+    fn process(&mut self, s: f64) -> f64 {
+        let out = self.b0 * s + self.z1;
+        self.z1 = self.b1 * s - self.a1 * out + self.z2;
+        self.z2 = self.b2 * s - self.a2 * out;
+        out
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum State {
     First,
     Normal,
     Suspend,
 }
 
+/// The state of a [Converter], see [Converter::snapshot].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    numer: usize,
+    denom: usize,
+    pos: usize,
+    offset: f64,
+    last_in: [f64; 2],
+    state: State,
+    filter: Vec<[f64; 2]>,
+}
+
+/// The anti-aliasing pre-filter for the step, empty if not downsampling.
+fn pre_filter(step: Rational64, anti_alias: bool) -> Vec<Biquad> {
+    let numer = *step.numer() as usize;
+    let denom = *step.denom() as usize;
+    let mut filter = Vec::new();
+    if anti_alias && numer > denom {
+        let freq = 0.5 * CUTOFF * denom as f64 / numer as f64;
+        filter.extend(BUTTERWORTH_Q.iter().map(|&q| Biquad::lowpass(freq, q)));
+    }
+    filter
+}
+
 pub struct Converter {
     numer: usize,
     denom: usize,
     pos: usize,
+    // phase below the resolution of pos, in input samples
+    offset: f64,
     coefs: Vec<f64>,
     last_in: [f64; 2],
     state: State,
+    filter: Vec<Biquad>,
 }
 
 impl Converter {
     #[inline]
-    fn new(step: Rational64) -> Self {
+    fn new(step: Rational64, anti_alias: bool, phase: f64) -> Self {
         let numer = *step.numer() as usize;
         let denom = *step.denom() as usize;
         let mut coefs = Vec::with_capacity(denom);
         for i in 0..denom {
             coefs.push(i as f64 / denom as f64);
         }
+        // advance by phase * step input samples, pos is in 1 / denom
+        let advance = phase * numer as f64;
+        let pos = math::floor(advance);
         Self {
             numer,
             denom,
-            pos: 0,
+            pos: pos as usize,
+            offset: (advance - pos) / denom as f64,
             coefs,
             last_in: [0.0; 2],
             state: State::First,
+            filter: pre_filter(step, anti_alias),
+        }
+    }
+
+    /// Take a snapshot of the position, the last samples and the pre-filter,
+    /// which can be restored to a `Converter` of the same `Manager` parameters.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            numer: self.numer,
+            denom: self.denom,
+            pos: self.pos,
+            offset: self.offset,
+            last_in: self.last_in,
+            state: self.state,
+            filter: self.filter.iter().map(|f| [f.z1, f.z2]).collect(),
         }
     }
+
+    /// Restore a snapshot, it fails if the ratio or anti-aliasing is
+    /// different, or the snapshot is corrupted.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<()> {
+        if snapshot.numer != self.numer
+            || snapshot.denom != self.denom
+            || snapshot.filter.len() != self.filter.len()
+            // the initial phase adds up to numer before the first output
+            || snapshot.pos >= self.denom + 2 * self.numer
+            || !(0.0..1.0 / self.denom as f64).contains(&snapshot.offset)
+        {
+            return Err(Error::SnapshotMismatch);
+        }
+        self.pos = snapshot.pos;
+        self.offset = snapshot.offset;
+        self.last_in = snapshot.last_in;
+        self.state = snapshot.state;
+        for (f, [z1, z2]) in self.filter.iter_mut().zip(&snapshot.filter) {
+            f.z1 = *z1;
+            f.z2 = *z2;
+        }
+        Ok(())
+    }
+
+    #[inline]
+    fn next_input<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        let s = iter.next()?;
+        Some(self.filter.iter_mut().fold(s, |s, f| f.process(s)))
+    }
 }
 
 impl Convert for Converter {
@@ -61,9 +202,9 @@ impl Convert for Converter {
         loop {
             match self.state {
                 State::First => {
-                    if let Some(s) = iter.next() {
+                    if let Some(s) = self.next_input(iter) {
                         self.last_in[1] = s;
-                        self.pos = self.numer;
+                        self.pos += self.numer;
                         self.state = State::Normal;
                     } else {
                         return None;
@@ -73,20 +214,20 @@ impl Convert for Converter {
                     while self.pos >= self.denom {
                         self.pos -= self.denom;
                         self.last_in[0] = self.last_in[1];
-                        if let Some(s) = iter.next() {
+                        if let Some(s) = self.next_input(iter) {
                             self.last_in[1] = s;
                         } else {
                             self.state = State::Suspend;
                             return None;
                         }
                     }
-                    let coef = self.coefs[self.pos];
+                    let coef = self.coefs[self.pos] + self.offset;
                     let interp = self.last_in[0] + (self.last_in[1] - self.last_in[0]) * coef;
                     self.pos += self.numer;
                     return Some(interp);
                 }
                 State::Suspend => {
-                    if let Some(s) = iter.next() {
+                    if let Some(s) = self.next_input(iter) {
                         self.last_in[1] = s;
                         self.state = State::Normal;
                     } else {
@@ -101,28 +242,200 @@ impl Convert for Converter {
 #[derive(Clone, Copy)]
 pub struct Manager {
     ratio: Rational64,
+    anti_alias: bool,
 }
 
 impl Manager {
     #[inline]
-    pub fn new(ratio: f64) -> Result<Self> {
-        let ratio = Rational64::approximate_float(ratio).unwrap_or_default();
-        if supported_ratio(ratio) {
-            Ok(Self { ratio })
+    fn new_internal(ratio: Rational64, anti_alias: bool) -> Result<Self> {
+        check_supported(ratio)?;
+        Ok(Self { ratio, anti_alias })
+    }
+
+    #[inline]
+    pub fn new<R>(ratio: R) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::new_internal(ratio.try_into()?.into(), false)
+    }
+
+    /// Create a `Manager` with the anti-aliasing pre-filter, which only works
+    /// when ratio < 1.
+    #[inline]
+    pub fn with_anti_alias<R>(ratio: R) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::new_internal(ratio.try_into()?.into(), true)
+    }
+
+    /// Create a `Manager` with the exact ratio of `new_sr / old_sr`.
+    ///
+    /// The sample rate ratio should in `[1/16, 16]` and the numerator after
+    /// reduction cannot be greater than 1024
+    #[inline]
+    pub fn with_sample_rate(old_sr: u32, new_sr: u32) -> Result<Self> {
+        let ratio = Ratio::from_sample_rate(old_sr, new_sr)?;
+        Self::new_internal(ratio.into(), false)
+    }
+
+    #[inline]
+    pub fn converter(&self) -> Converter {
+        self.converter_with_phase(0.0)
+    }
+
+    /// Create a `Converter` whose output is advanced by `phase` output samples
+    /// in `[0, 1)`, so its group delay is `group_delay() - phase`, see
+    /// [sinc::Manager::converter_with_phase](crate::sinc::Manager::converter_with_phase).
+    #[inline]
+    pub fn converter_with_phase(&self, phase: f64) -> Converter {
+        let phase = phase.clamp(0.0, 1.0 - f64::EPSILON);
+        Converter::new(self.ratio.recip(), self.anti_alias, phase)
+    }
+
+    /// Get the group delay in output samples, which is negative when
+    /// downsampling without the pre-filter, as the output leads the input.
+    #[inline]
+    pub fn group_delay(&self) -> f64 {
+        self.input_group_delay() * *self.ratio.numer() as f64 / *self.ratio.denom() as f64
+    }
+
+    /// Get the group delay in input samples, that is `1 - 1 / ratio` and the
+    /// pre-filter at DC.
+    pub fn input_group_delay(&self) -> f64 {
+        let step = self.ratio.recip();
+        let filter: f64 = pre_filter(step, self.anti_alias)
+            .iter()
+            .map(Biquad::dc_delay)
+            .sum();
+        1.0 - *step.numer() as f64 / *step.denom() as f64 + filter
+    }
+
+    /// Estimate the cost of a `Converter`, the pre-filter runs on each input
+    /// sample.
+    pub fn cost(&self) -> Cost {
+        let stages = if self.anti_alias && self.ratio < Rational64::ONE {
+            BUTTERWORTH_Q.len()
         } else {
-            Err(Error::UnsupportedRatio)
+            0
+        };
+        let inputs = (*self.ratio.denom() as f64) / (*self.ratio.numer() as f64);
+        Cost {
+            mul_adds: 1.0 + (5 * stages) as f64 * inputs,
+            lookups: 0.0,
+            history: 2,
+            memory: size_of::<Converter>()
+                + stages * size_of::<Biquad>()
+                + *self.ratio.numer() as usize * size_of::<f64>(),
         }
     }
 
+    /// Create a `Builder` to build `Manager`
     #[inline]
-    pub fn converter(&self) -> Converter {
-        Converter::new(self.ratio.recip())
+    pub fn builder() -> Builder {
+        Builder::default()
+    }
+
+    /// Create a `Manager` from a linear [Config], with ratio or sample rates
+    /// and anti-aliasing.
+    ///
+    /// The parameters of sinc are not used, it fails with
+    /// [Error::IgnoredParam] if any of them is set.
+    pub fn from_config(config: &Config) -> Result<Self> {
+        config.expect(Algorithm::Linear)?;
+        let ignored = [
+            ("attenuation", config.atten.is_some()),
+            ("quantify", config.quan.is_some()),
+            ("trans_width", config.trans_width.is_some()),
+            ("order", config.order.is_some()),
+            ("kaiser_beta", config.kaiser_beta.is_some()),
+            ("cutoff", config.cutoff.is_some()),
+            ("pass_freq", config.pass_freq.is_some()),
+        ];
+        if let Some((name, _)) = ignored.iter().find(|(_, set)| *set) {
+            return Err(Error::IgnoredParam(name));
+        }
+        let builder = Builder {
+            ratio: config.ratio.map(Ok),
+            old_sr: config.old_sr,
+            new_sr: config.new_sr,
+            anti_alias: config.anti_alias,
+        };
+        builder.build()
+    }
+
+    /// Get the parameters as a [Config], which builds the same `Manager`.
+    pub fn config(&self) -> Config {
+        Config {
+            ratio: Some(Ratio::try_from(self.ratio).unwrap()),
+            anti_alias: self.anti_alias,
+            ..Config::new(Algorithm::Linear)
+        }
+    }
+}
+
+/// The Builder to build `Manager`
+///
+/// ```
+/// use simple_src::linear;
+///
+/// let manager = linear::Manager::builder()
+///     .sample_rate(96000, 44100)
+///     .anti_alias(true)
+///     .build();
+/// assert!(manager.is_ok());
+/// ```
+#[derive(Default)]
+pub struct Builder {
+    ratio: Option<Result<Ratio>>,
+    old_sr: Option<u32>,
+    new_sr: Option<u32>,
+    anti_alias: bool,
+}
+
+impl Builder {
+    /// Set `ratio`, in `[1/16, 16]`, the numerator after reduction should <= 1024
+    pub fn ratio<R>(mut self, ratio: R) -> Self
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        self.ratio = Some(ratio.try_into().map_err(Error::from));
+        self
+    }
+
+    /// Set old sample rate and new sample rate
+    pub fn sample_rate(mut self, old_sr: u32, new_sr: u32) -> Self {
+        self.old_sr = Some(old_sr);
+        self.new_sr = Some(new_sr);
+        self
+    }
+
+    /// Enable the anti-aliasing pre-filter, default is disabled
+    pub fn anti_alias(mut self, enable: bool) -> Self {
+        self.anti_alias = enable;
+        self
+    }
+
+    /// Build the `Manager` with ratio or sample_rate, ratio is used if both
+    /// are set.
+    pub fn build(self) -> Result<Manager> {
+        let ratio = match (self.ratio, self.old_sr, self.new_sr) {
+            (Some(ratio), _, _) => ratio?,
+            (_, Some(old_sr), Some(new_sr)) => Ratio::from_sample_rate(old_sr, new_sr)?,
+            _ => return Err(Error::NotEnoughParam),
+        };
+        Manager::new_internal(ratio.into(), self.anti_alias)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::RatioError;
 
     #[test]
     fn test_manager_ok() {
@@ -148,4 +461,153 @@ mod tests {
             assert!(Manager::new(ratio).is_err());
         }
     }
+
+    #[test]
+    fn test_with_sample_rate() {
+        assert!(Manager::with_sample_rate(44100, 48000).is_ok());
+        assert!(Manager::with_sample_rate(8000, 192000).is_err());
+        assert!(Manager::with_sample_rate(0, 48000).is_err());
+        let manager = Manager::with_sample_rate(48000, 44100).unwrap();
+        assert_eq!(manager.ratio, Rational64::new(147, 160));
+    }
+
+    #[test]
+    fn test_builder() {
+        let manager = Manager::builder()
+            .sample_rate(48000, 44100)
+            .build()
+            .unwrap();
+        assert_eq!(manager.ratio, Rational64::new(147, 160));
+        assert!(!manager.anti_alias);
+        let manager = Manager::builder()
+            .ratio(0.5)
+            .anti_alias(true)
+            .build()
+            .unwrap();
+        assert!(manager.anti_alias);
+        assert!(matches!(
+            Manager::builder().anti_alias(true).build(),
+            Err(Error::NotEnoughParam)
+        ));
+        assert!(matches!(
+            Manager::builder().sample_rate(0, 44100).build(),
+            Err(Error::UnsupportedRatio(RatioError::NotFinite))
+        ));
+        assert!(matches!(
+            Manager::builder().ratio(f64::NAN).build(),
+            Err(Error::UnsupportedRatio(RatioError::NotFinite))
+        ));
+    }
+
+    #[test]
+    fn test_config() {
+        let config = "linear:old_sr=48000,new_sr=44100,aa=true".parse().unwrap();
+        let manager = Manager::from_config(&config).unwrap();
+        assert_eq!(manager.config().to_string(), "linear:ratio=147/160,aa=true");
+        let sinc = "sinc:ratio=2".parse().unwrap();
+        assert!(Manager::from_config(&sinc).is_err());
+        let config = "linear:ratio=2,atten=96".parse().unwrap();
+        assert_eq!(
+            Manager::from_config(&config).err(),
+            Some(Error::IgnoredParam("attenuation"))
+        );
+        let config = "linear:ratio=2,pass_freq=20000".parse().unwrap();
+        assert_eq!(
+            Manager::from_config(&config).err(),
+            Some(Error::IgnoredParam("pass_freq"))
+        );
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let manager = Manager::with_anti_alias(0.75).unwrap();
+        let input: Vec<f64> = (0..300).map(|i| (i as f64 * 0.1).sin()).collect();
+        let mut converter = manager.converter();
+        let _: Vec<f64> = converter.process(input[..100].iter().copied()).collect();
+        let snapshot = converter.snapshot();
+        let tail: Vec<f64> = converter.process(input[100..].iter().copied()).collect();
+        let mut restored = manager.converter();
+        restored.restore(&snapshot).unwrap();
+        let again: Vec<f64> = restored.process(input[100..].iter().copied()).collect();
+        assert_eq!(tail, again);
+        let plain = Manager::new(0.75).unwrap();
+        assert_eq!(
+            plain.converter().restore(&snapshot),
+            Err(Error::SnapshotMismatch)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_phase() {
+        let manager = Manager::new(0.3).unwrap();
+        let input: Vec<f64> = (0..100).map(|i| (i as f64 * 0.1).sin()).collect();
+        let mut converter = manager.converter_with_phase(0.9);
+        // suspended after the first sample, before any output
+        let head: Vec<f64> = converter.process(input[..1].iter().copied()).collect();
+        assert!(head.is_empty());
+        let snapshot = converter.snapshot();
+        let tail: Vec<f64> = converter.process(input[1..].iter().copied()).collect();
+        let mut restored = manager.converter_with_phase(0.9);
+        restored.restore(&snapshot).unwrap();
+        let again: Vec<f64> = restored.process(input[1..].iter().copied()).collect();
+        assert_eq!(tail, again);
+    }
+
+    fn amplitude(manager: Manager, freq: f64) -> f64 {
+        let input = (0..4096).map(|i| (i as f64 * 2.0 * PI * freq).sin());
+        let output: Vec<f64> = manager.converter().process(input).collect();
+        output[1024..].iter().fold(0.0, |a, s| a.max(s.abs()))
+    }
+
+    #[test]
+    fn test_anti_alias() {
+        let plain = Manager::new(0.5).unwrap();
+        let filtered = Manager::with_anti_alias(0.5).unwrap();
+        assert!(amplitude(plain, 0.4) > 0.5);
+        assert!(amplitude(filtered, 0.4) < 1e-3);
+        assert!(amplitude(filtered, 0.05) > 0.99);
+        // no filter when upsampling
+        let output: Vec<f64> = Manager::with_anti_alias(2.0)
+            .unwrap()
+            .converter()
+            .process([1.0, 1.0].into_iter())
+            .collect();
+        assert_eq!(output, [0.5, 1.0, 1.0]);
+    }
+
+    #[test]
+    fn test_cost() {
+        let cost = Manager::new(0.5).unwrap().cost();
+        assert_eq!(cost.mul_adds, 1.0);
+        assert_eq!(cost.lookups, 0.0);
+        assert_eq!(cost.history, 2);
+        let cost = Manager::with_anti_alias(0.5).unwrap().cost();
+        assert_eq!(cost.mul_adds, 31.0);
+        let cost = Manager::with_anti_alias(2.0).unwrap().cost();
+        assert_eq!(cost.mul_adds, 1.0);
+    }
+
+    #[test]
+    fn test_group_delay() {
+        assert_eq!(Manager::new(2.0).unwrap().group_delay(), 1.0);
+        assert_eq!(Manager::new(0.5).unwrap().input_group_delay(), -1.0);
+        // the output of a ramp is the ramp delayed by the group delay
+        for (ratio, anti_alias) in [(2.0, false), (0.75, false), (0.5, true), (0.3, true)] {
+            let manager = if anti_alias {
+                Manager::with_anti_alias(ratio).unwrap()
+            } else {
+                Manager::new(ratio).unwrap()
+            };
+            let delay = manager.group_delay();
+            for phase in [0.0, 0.3, 0.9] {
+                let mut converter = manager.converter_with_phase(phase);
+                let ramp = (0..2000).map(|i| i as f64);
+                let output: Vec<f64> = converter.process(ramp).collect();
+                for (k, s) in output.iter().enumerate().skip(200).take(100) {
+                    let expected = (k as f64 - delay + phase) / ratio;
+                    assert!((s - expected).abs() < 1e-6, "{ratio} {phase} {k}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,119 @@
+//! Float functions which use `std` if enabled, otherwise `libm`.
+
+#[cfg(feature = "std")]
+mod imp {
+    #[inline]
+    pub fn sin(x: f64) -> f64 {
+        x.sin()
+    }
+
+    #[inline]
+    pub fn cos(x: f64) -> f64 {
+        x.cos()
+    }
+
+    #[inline]
+    pub fn sqrt(x: f64) -> f64 {
+        x.sqrt()
+    }
+
+    #[inline]
+    pub fn powi(x: f64, n: i32) -> f64 {
+        x.powi(n)
+    }
+
+    #[inline]
+    pub fn powf(x: f64, n: f64) -> f64 {
+        x.powf(n)
+    }
+
+    #[inline]
+    pub fn log2(x: f64) -> f64 {
+        x.log2()
+    }
+
+    #[inline]
+    pub fn log10(x: f64) -> f64 {
+        x.log10()
+    }
+
+    #[inline]
+    pub fn floor(x: f64) -> f64 {
+        x.floor()
+    }
+
+    #[inline]
+    pub fn ceil(x: f64) -> f64 {
+        x.ceil()
+    }
+
+    #[inline]
+    pub fn round(x: f64) -> f64 {
+        x.round()
+    }
+
+    #[inline]
+    pub fn abs(x: f64) -> f64 {
+        x.abs()
+    }
+}
+
+#[cfg(not(feature = "std"))]
+mod imp {
+    #[inline]
+    pub fn sin(x: f64) -> f64 {
+        libm::sin(x)
+    }
+
+    #[inline]
+    pub fn cos(x: f64) -> f64 {
+        libm::cos(x)
+    }
+
+    #[inline]
+    pub fn sqrt(x: f64) -> f64 {
+        libm::sqrt(x)
+    }
+
+    #[inline]
+    pub fn powi(x: f64, n: i32) -> f64 {
+        libm::pow(x, n as f64)
+    }
+
+    #[inline]
+    pub fn powf(x: f64, n: f64) -> f64 {
+        libm::pow(x, n)
+    }
+
+    #[inline]
+    pub fn log2(x: f64) -> f64 {
+        libm::log2(x)
+    }
+
+    #[inline]
+    pub fn log10(x: f64) -> f64 {
+        libm::log10(x)
+    }
+
+    #[inline]
+    pub fn floor(x: f64) -> f64 {
+        libm::floor(x)
+    }
+
+    #[inline]
+    pub fn ceil(x: f64) -> f64 {
+        libm::ceil(x)
+    }
+
+    #[inline]
+    pub fn round(x: f64) -> f64 {
+        libm::round(x)
+    }
+
+    #[inline]
+    pub fn abs(x: f64) -> f64 {
+        libm::fabs(x)
+    }
+}
+
+pub(crate) use imp::*;
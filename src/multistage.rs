@@ -0,0 +1,416 @@
+//! Multi-stage converter
+//!
+//! The order of a single sinc filter grows with `1 / ratio` when
+//! downsampling, so a conversion like 192kHz to 44.1kHz gets expensive. This
+//! converter splits the conversion into [halfband] stages (ratio 2 or 1/2) and
+//! one fractional [sinc] stage, and picks the split with the lowest cost.
+//!
+//! ```
+//! use simple_src::{multistage, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = multistage::Manager::with_sample_rate(192000, 44100, 96.0, 128, 20000).unwrap();
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+//!
+//! The half-band stages only let aliasing or imaging into the transition band
+//! of the whole conversion, the pass band is protected by `atten`.
+
+use alloc::vec::Vec;
+use core::f64::consts::PI;
+
+use num_rational::Rational64;
+
+use crate::ratio::{check_supported, Ratio};
+use crate::{halfband, kaiser, math, sinc};
+
+use super::{check_range, Convert, Error, Result};
+
+enum StageDesign {
+    Sinc {
+        ratio: Rational64,
+        order: u32,
+        cutoff: f64,
+    },
+    HalfDown {
+        trans_width: f64,
+        taps: usize,
+    },
+    HalfUp {
+        trans_width: f64,
+        taps: usize,
+    },
+}
+
+impl StageDesign {
+    #[inline]
+    fn ratio(&self) -> f64 {
+        match self {
+            StageDesign::Sinc { ratio, .. } => to_f64(*ratio),
+            StageDesign::HalfDown { .. } => 0.5,
+            StageDesign::HalfUp { .. } => 2.0,
+        }
+    }
+
+    /// Multiply-adds per output sample of this stage.
+    #[inline]
+    fn cost(&self) -> f64 {
+        match self {
+            StageDesign::Sinc { order, .. } => (order + 1) as f64,
+            StageDesign::HalfDown { taps, .. } => (taps + 1) as f64,
+            StageDesign::HalfUp { taps, .. } => (taps + 1) as f64 * 0.5,
+        }
+    }
+}
+
+#[inline]
+fn to_f64(ratio: Rational64) -> f64 {
+    *ratio.numer() as f64 / *ratio.denom() as f64
+}
+
+/// Design a sinc stage, `in_rate`, `pass` and `stop` are normalized so that
+/// the Nyquist frequency of the lower sample rate of the whole conversion is 1.
+#[inline]
+fn design_sinc(
+    ratio: Rational64,
+    in_rate: f64,
+    pass: f64,
+    stop: f64,
+    atten: f64,
+) -> Option<StageDesign> {
+    let order = math::ceil((atten - 8.0) * in_rate / (2.285 * 2.0 * PI * (stop - pass)));
+    let cutoff = (pass + stop) / in_rate;
+    if order > sinc::MAX_ORDER as f64 || !(0.01..=1.0).contains(&cutoff) {
+        return None;
+    }
+    Some(StageDesign::Sinc {
+        ratio,
+        order: (order as u32).max(1),
+        cutoff,
+    })
+}
+
+/// Design a half-band stage, `low_rate` is the lower sample rate of the stage,
+/// the aliasing or imaging only falls in `[pass, 1]`.
+#[inline]
+fn design_half(low_rate: f64, pass: f64, atten: f64, up: bool) -> Option<StageDesign> {
+    let trans_width = (2.0 * (low_rate - 2.0 * pass) / low_rate).min(1.0);
+    let taps = halfband::calc_taps(atten, trans_width);
+    if taps > halfband::MAX_TAPS {
+        return None;
+    }
+    if up {
+        Some(StageDesign::HalfUp { trans_width, taps })
+    } else {
+        Some(StageDesign::HalfDown { trans_width, taps })
+    }
+}
+
+/// Plan the stages with `halves` half-band stages, they are placed at the
+/// higher sample rate side of the conversion.
+fn plan(ratio: Rational64, atten: f64, trans_width: f64, halves: u32) -> Option<Vec<StageDesign>> {
+    let one = Rational64::from_integer(1);
+    let two = Rational64::from_integer(2);
+    let pass = 1.0 - trans_width;
+    let mut stages = Vec::new();
+    if ratio < one {
+        let frac = ratio * two.pow(halves as i32);
+        if frac > one {
+            return None;
+        }
+        let mut rate = 2.0 / to_f64(ratio);
+        for _ in 0..halves {
+            rate *= 0.5;
+            stages.push(design_half(rate, pass, atten, false)?);
+        }
+        if frac != one {
+            stages.push(design_sinc(frac, rate, pass, 1.0, atten)?);
+        }
+    } else {
+        let frac = ratio / two.pow(halves as i32);
+        if frac < one {
+            return None;
+        }
+        let mut rate = 2.0;
+        if frac != one {
+            stages.push(design_sinc(frac, rate, pass, 1.0, atten)?);
+        }
+        rate *= to_f64(frac);
+        for _ in 0..halves {
+            stages.push(design_half(rate, pass, atten, true)?);
+            rate *= 2.0;
+        }
+    }
+    Some(stages)
+}
+
+/// Multiply-adds per output sample of the whole chain.
+#[inline]
+fn plan_cost(stages: &[StageDesign]) -> f64 {
+    let mut cost = 0.0;
+    let mut weight = 1.0;
+    for stage in stages.iter().rev() {
+        cost += stage.cost() * weight;
+        weight /= stage.ratio();
+    }
+    cost
+}
+
+#[derive(Clone)]
+enum Stage {
+    Sinc(sinc::Manager),
+    HalfDown(halfband::Manager),
+    HalfUp(halfband::Manager),
+}
+
+enum StageConverter {
+    Sinc(sinc::Converter),
+    HalfDown(halfband::Downsampler),
+    HalfUp(halfband::Upsampler),
+}
+
+impl Convert for StageConverter {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        match self {
+            StageConverter::Sinc(c) => c.next_sample(iter),
+            StageConverter::HalfDown(c) => c.next_sample(iter),
+            StageConverter::HalfUp(c) => c.next_sample(iter),
+        }
+    }
+}
+
+struct Chain<'a, I> {
+    stages: &'a mut [StageConverter],
+    iter: &'a mut I,
+}
+
+impl<I> Iterator for Chain<'_, I>
+where
+    I: Iterator<Item = f64>,
+{
+    type Item = f64;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        match self.stages.split_last_mut() {
+            Some((last, rest)) => last.next_sample(&mut Chain {
+                stages: rest,
+                iter: &mut *self.iter,
+            }),
+            None => self.iter.next(),
+        }
+    }
+}
+
+pub struct Converter {
+    stages: Vec<StageConverter>,
+}
+
+impl Convert for Converter {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        Chain {
+            stages: &mut self.stages,
+            iter,
+        }
+        .next()
+    }
+}
+
+#[derive(Clone)]
+pub struct Manager {
+    stages: Vec<Stage>,
+    latency: usize,
+}
+
+impl Manager {
+    fn new_internal(ratio: Rational64, atten: f64, quan: u32, trans_width: f64) -> Result<Self> {
+        check_supported(ratio)?;
+        check_range("atten", atten, sinc::MIN_ATTEN, sinc::MAX_ATTEN)?;
+        check_range("quan", quan, sinc::MIN_QUAN, sinc::MAX_QUAN)?;
+        check_range("trans_width", trans_width, 0.01, 1.0)?;
+        let fratio = to_f64(ratio);
+        let max_halves = math::floor(math::log2(fratio.max(fratio.recip()))) as u32;
+        let best = (0..=max_halves)
+            .filter_map(|halves| plan(ratio, atten, trans_width, halves))
+            .min_by(|a, b| plan_cost(a).total_cmp(&plan_cost(b)))
+            // the filters are too long for the transition band
+            .ok_or(Error::InvalidParam {
+                name: "trans_width",
+                value: trans_width,
+            })?;
+        let kaiser_beta = kaiser::calc_kaiser_beta(atten);
+        let mut stages = Vec::with_capacity(best.len());
+        let mut delay = 0.0;
+        for design in best {
+            delay *= design.ratio();
+            let stage = match design {
+                StageDesign::Sinc {
+                    ratio,
+                    order,
+                    cutoff,
+                } => {
+                    let manager =
+                        sinc::Manager::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)?;
+                    delay += manager.group_delay();
+                    Stage::Sinc(manager)
+                }
+                StageDesign::HalfDown { trans_width, .. } => {
+                    let manager = halfband::Manager::new(atten, trans_width)?;
+                    delay += manager.down_latency() as f64;
+                    Stage::HalfDown(manager)
+                }
+                StageDesign::HalfUp { trans_width, .. } => {
+                    let manager = halfband::Manager::new(atten, trans_width)?;
+                    delay += manager.up_latency() as f64;
+                    Stage::HalfUp(manager)
+                }
+            };
+            stages.push(stage);
+        }
+        Ok(Self {
+            stages,
+            latency: math::round(delay) as usize,
+        })
+    }
+
+    /// Create a `Manager` with attenuation, quantify and transition band width.
+    ///
+    /// The stages and their orders will be calculated.
+    ///
+    /// - ratio: the conversion ratio, fs_new / fs_old, support `[1/16, 16]`,
+    ///   the numerator after reduction should <= 1024
+    /// - atten: the attenuation in dB, support `[12.0, 180.0]`
+    /// - quan: the quantify number of the fractional stage, support `[1, 16384]`
+    /// - trans_width: the transition band width in `[0.01, 1.0]`
+    #[inline]
+    pub fn new<R>(ratio: R, atten: f64, quan: u32, trans_width: f64) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::new_internal(ratio.try_into()?.into(), atten, quan, trans_width)
+    }
+
+    /// Create a `Manager` with sample rate, attenuation, quantify and pass frequency
+    ///
+    /// - old_sr: Old sample rate, not 0
+    /// - new_sr: New sample rate, not 0
+    /// - atten: `[12.0, 180.0]`
+    /// - quan: `[1, 16384]`
+    /// - pass_freq: the calculated transition band width should in `[0.01, 1.0]`
+    ///
+    /// The sample rate ratio should in `[1/16, 16]` and the numerator after
+    /// reduction cannot be greater than 1024
+    #[inline]
+    pub fn with_sample_rate(
+        old_sr: u32,
+        new_sr: u32,
+        atten: f64,
+        quan: u32,
+        pass_freq: u32,
+    ) -> Result<Self> {
+        let ratio = Ratio::from_sample_rate(old_sr, new_sr)?.into();
+        let trans_width = sinc::pass_freq_trans_width(old_sr, new_sr, pass_freq)?;
+        Self::new_internal(ratio, atten, quan, trans_width)
+    }
+
+    /// Create a `Converter` which runs all the stages.
+    #[inline]
+    pub fn converter(&self) -> Converter {
+        Converter {
+            stages: self
+                .stages
+                .iter()
+                .map(|stage| match stage {
+                    Stage::Sinc(m) => StageConverter::Sinc(m.converter()),
+                    Stage::HalfDown(m) => StageConverter::HalfDown(m.downsampler()),
+                    Stage::HalfUp(m) => StageConverter::HalfUp(m.upsampler()),
+                })
+                .collect(),
+        }
+    }
+
+    /// Get the latency of the whole chain in output samples.
+    #[inline]
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    /// Get the number of stages, 0 if the ratio is 1.
+    #[inline]
+    pub fn stage_count(&self) -> usize {
+        self.stages.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_manager_new() {
+        assert!(Manager::new(0.25, 96.0, 128, 0.1).is_ok());
+        assert!(Manager::new(4.0, 96.0, 128, 0.1).is_ok());
+        assert!(Manager::new(1.0, 96.0, 128, 0.1).is_ok());
+        assert!(Manager::new(0.01, 96.0, 128, 0.1).is_err());
+        assert!(Manager::new(0.25, 11.9, 128, 0.1).is_err());
+        assert!(Manager::new(0.25, 96.0, 0, 0.1).is_err());
+        assert!(Manager::new(0.25, 96.0, 128, 0.0).is_err());
+        assert!(Manager::with_sample_rate(0, 44100, 96.0, 128, 20000).is_err());
+    }
+
+    #[test]
+    fn test_plan() {
+        let ratio = Rational64::new(44100, 192000);
+        let single = plan(ratio, 120.0, 0.09, 0).unwrap();
+        let best = (0..=2)
+            .filter_map(|halves| plan(ratio, 120.0, 0.09, halves))
+            .min_by(|a, b| plan_cost(a).total_cmp(&plan_cost(b)))
+            .unwrap();
+        assert!(best.len() > 1);
+        assert!(plan_cost(&best) < plan_cost(&single));
+        let manager = Manager::with_sample_rate(192000, 44100, 120.0, 512, 20000).unwrap();
+        assert_eq!(manager.stage_count(), best.len());
+        let manager = Manager::new(0.5, 120.0, 512, 0.1).unwrap();
+        assert_eq!(manager.stage_count(), 1);
+    }
+
+    #[test]
+    fn test_latency() {
+        for ratio in [0.125, 44100.0 / 96000.0, 96000.0 / 44100.0, 8.0] {
+            let manager = Manager::new(ratio, 72.0, 64, 0.2).unwrap();
+            let impulse = (0..4096).map(|i| if i == 256 { 1.0 } else { 0.0 });
+            let output: Vec<f64> = manager.converter().process(impulse).collect();
+            let peak = output
+                .iter()
+                .enumerate()
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .unwrap()
+                .0;
+            let expected = manager.latency() as f64 + 256.0 * ratio;
+            assert!((peak as f64 - expected).abs() <= 1.0);
+        }
+    }
+
+    #[test]
+    fn test_dc_gain() {
+        for ratio in [0.125, 0.3, 3.0, 16.0] {
+            let manager = Manager::new(ratio, 72.0, 64, 0.2).unwrap();
+            let ones = std::iter::repeat_n(1.0, 2048);
+            let output: Vec<f64> = manager.converter().process(ones).collect();
+            let s = output[output.len() / 2];
+            assert!((s - 1.0).abs() < 1e-3);
+        }
+    }
+}
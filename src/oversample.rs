@@ -0,0 +1,197 @@
+//! Oversampling for nonlinear processing
+//!
+//! Distortion or saturation creates harmonics above the Nyquist frequency,
+//! which alias back at base rate. The [Oversampler] upsamples by 2, 4, 8 or
+//! 16 with cascaded [halfband] stages, calls the closure at
+//! the oversampled rate and downsamples back.
+//!
+//! ```
+//! use simple_src::oversample::Oversampler;
+//!
+//! let input = vec![0.1, 0.5, 0.9, 0.5];
+//! let mut output = vec![0.0; input.len()];
+//! let mut oversampler = Oversampler::new(4, 96.0, 0.1).unwrap();
+//! oversampler.process(&input, &mut output, |s| s.tanh());
+//! ```
+//!
+//! The output is delayed by exactly [Oversampler::latency] samples at base
+//! rate, so it stays aligned with the dry signal after compensation.
+
+use alloc::collections::VecDeque;
+use alloc::vec;
+use alloc::vec::Vec;
+
+use crate::halfband;
+use crate::Convert;
+
+use super::{check_range, Error, Result};
+
+pub struct Oversampler {
+    factor: usize,
+    ups: Vec<halfband::Upsampler>,
+    downs: Vec<halfband::Downsampler>,
+    delay: VecDeque<f64>,
+    latency: usize,
+    buf: Vec<f64>,
+    tmp: Vec<f64>,
+}
+
+impl Oversampler {
+    /// Create an `Oversampler` with factor, attenuation and transition band width.
+    ///
+    /// - factor: the oversampling factor, support 2, 4, 8 and 16
+    /// - atten: the attenuation in dB, support `[12.0, 180.0]`
+    /// - trans_width: the transition band width relative to the base Nyquist
+    ///   frequency in `[0.01, 1.0]`, centred on it
+    pub fn new(factor: usize, atten: f64, trans_width: f64) -> Result<Self> {
+        if ![2, 4, 8, 16].contains(&factor) {
+            return Err(Error::InvalidParam {
+                name: "factor",
+                value: factor as f64,
+            });
+        }
+        check_range("trans_width", trans_width, 0.01, 1.0)?;
+        let levels = factor.trailing_zeros();
+        let pass = 1.0 - 0.5 * trans_width;
+        let mut ups = Vec::with_capacity(levels as usize);
+        let mut downs = Vec::with_capacity(levels as usize);
+        // total latency at the oversampled rate
+        let mut delay = 0;
+        for level in 0..levels {
+            // the Nyquist frequency of the lower rate, relative to base rate
+            let low_nyquist = (1 << level) as f64;
+            let level_width = (2.0 - 2.0 * pass / low_nyquist).min(1.0);
+            let manager = halfband::Manager::new(atten, level_width)?;
+            let up = manager.upsampler();
+            let down = manager.downsampler();
+            let round_trip = up.latency() + 2 * down.latency();
+            delay += round_trip << (levels - level - 1);
+            ups.push(up);
+            downs.push(down);
+        }
+        let pad = (factor - delay % factor) % factor;
+        Ok(Self {
+            factor,
+            ups,
+            downs,
+            delay: VecDeque::from(vec![0.0; pad]),
+            latency: (delay + pad) / factor,
+            buf: Vec::new(),
+            tmp: Vec::new(),
+        })
+    }
+
+    /// Get the oversampling factor.
+    #[inline]
+    pub fn factor(&self) -> usize {
+        self.factor
+    }
+
+    /// Get the latency in base rate samples.
+    #[inline]
+    pub fn latency(&self) -> usize {
+        self.latency
+    }
+
+    fn upsample(&mut self, input: &[f64]) {
+        self.buf.clear();
+        self.buf.extend_from_slice(input);
+        for up in self.ups.iter_mut() {
+            self.tmp.clear();
+            self.tmp.extend(up.process(self.buf.iter().copied()));
+            core::mem::swap(&mut self.buf, &mut self.tmp);
+        }
+        if !self.delay.is_empty() {
+            for s in self.buf.iter_mut() {
+                self.delay.push_back(*s);
+                *s = self.delay.pop_front().unwrap();
+            }
+        }
+    }
+
+    fn downsample(&mut self, output: &mut [f64]) {
+        for down in self.downs.iter_mut().rev() {
+            self.tmp.clear();
+            self.tmp.extend(down.process(self.buf.iter().copied()));
+            core::mem::swap(&mut self.buf, &mut self.tmp);
+        }
+        output.copy_from_slice(&self.buf);
+    }
+
+    /// Process a block, `f` is called for each oversampled sample.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths of `input` and `output` are different.
+    pub fn process<F>(&mut self, input: &[f64], output: &mut [f64], mut f: F)
+    where
+        F: FnMut(f64) -> f64,
+    {
+        assert_eq!(input.len(), output.len());
+        self.upsample(input);
+        for s in self.buf.iter_mut() {
+            *s = f(*s);
+        }
+        self.downsample(output);
+    }
+
+    /// Process a block, `f` is called once with all the oversampled samples
+    /// of the block.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the lengths of `input` and `output` are different.
+    pub fn process_block<F>(&mut self, input: &[f64], output: &mut [f64], f: F)
+    where
+        F: FnOnce(&mut [f64]),
+    {
+        assert_eq!(input.len(), output.len());
+        self.upsample(input);
+        f(&mut self.buf);
+        self.downsample(output);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(Oversampler::new(2, 96.0, 0.1).is_ok());
+        assert!(Oversampler::new(16, 96.0, 0.1).is_ok());
+        assert!(Oversampler::new(3, 96.0, 0.1).is_err());
+        assert!(Oversampler::new(32, 96.0, 0.1).is_err());
+        assert!(Oversampler::new(4, 11.9, 0.1).is_err());
+        assert!(Oversampler::new(4, 96.0, 0.0).is_err());
+    }
+
+    #[test]
+    fn test_aligned() {
+        let input: Vec<f64> = (0..2048).map(|i| (i as f64 * 0.2).sin()).collect();
+        for factor in [2, 4, 8, 16] {
+            let mut oversampler = Oversampler::new(factor, 120.0, 0.2).unwrap();
+            let mut output = vec![0.0; input.len()];
+            for (i, o) in input.chunks(100).zip(output.chunks_mut(100)) {
+                oversampler.process(i, o, |s| s);
+            }
+            let latency = oversampler.latency();
+            for i in 512..1024 {
+                assert!((output[i + latency] - input[i]).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_block() {
+        let mut oversampler = Oversampler::new(8, 96.0, 0.1).unwrap();
+        let input = [0.5; 64];
+        let mut output = [0.0; 64];
+        let mut len = 0;
+        oversampler.process_block(&input, &mut output, |buf| {
+            len = buf.len();
+            buf.iter_mut().for_each(|s| *s *= 2.0);
+        });
+        assert_eq!(len, 64 * 8);
+    }
+}
@@ -0,0 +1,199 @@
+//! Polynomial interpolation converter
+//!
+//! The converter of [hermite](crate::hermite), [lagrange](crate::lagrange) and
+//! [optimal](crate::optimal), generic over the [Kernel] which gives the
+//! weights of `N` points. The weights are calculated once for each position.
+//!
+//! ```
+//! use simple_src::{hermite::Hermite, poly, Convert};
+//!
+//! let samples = vec![1.0, 2.0, 3.0, 4.0];
+//! let manager = poly::Manager::<Hermite, 4>::new(2.0).unwrap();
+//! let mut converter = manager.converter();
+//! for s in converter.process(samples.into_iter()) {
+//!     println!("{s}");
+//! }
+//! ```
+
+use core::marker::PhantomData;
+
+use alloc::vec::Vec;
+
+use num_rational::Rational64;
+
+use crate::ratio::{check_supported, Ratio};
+
+use super::{Convert, Error, Result};
+
+/// Weights of an `N` point polynomial interpolation.
+pub trait Kernel<const N: usize> {
+    /// Weights of the `N` points at `1 - N / 2, ..., N / 2` for position `x`
+    /// in `[0, 1)`.
+    fn weights(x: f64) -> [f64; N];
+}
+
+enum State {
+    First,
+    Normal,
+    Suspend,
+}
+
+pub struct Converter<const N: usize> {
+    numer: usize,
+    denom: usize,
+    pos: usize,
+    coefs: Vec<[f64; N]>,
+    last_in: [f64; N],
+    state: State,
+}
+
+impl<const N: usize> Converter<N> {
+    #[inline]
+    fn new(step: Rational64, weights: fn(f64) -> [f64; N]) -> Self {
+        let numer = *step.numer() as usize;
+        let denom = *step.denom() as usize;
+        let mut coefs = Vec::with_capacity(denom);
+        for i in 0..denom {
+            coefs.push(weights(i as f64 / denom as f64));
+        }
+        Self {
+            numer,
+            denom,
+            pos: 0,
+            coefs,
+            last_in: [0.0; N],
+            state: State::First,
+        }
+    }
+
+    #[inline]
+    fn interpolate(&self) -> f64 {
+        let coef = &self.coefs[self.pos];
+        let mut interp = 0.0;
+        for (s, w) in self.last_in.iter().zip(coef) {
+            interp += s * w;
+        }
+        interp
+    }
+}
+
+impl<const N: usize> Convert for Converter<N> {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        loop {
+            match self.state {
+                State::First => {
+                    if let Some(s) = iter.next() {
+                        self.last_in[N - 1] = s;
+                        self.pos = self.numer;
+                        self.state = State::Normal;
+                    } else {
+                        return None;
+                    }
+                }
+                State::Normal => {
+                    while self.pos >= self.denom {
+                        self.pos -= self.denom;
+                        self.last_in.copy_within(1.., 0);
+                        if let Some(s) = iter.next() {
+                            self.last_in[N - 1] = s;
+                        } else {
+                            self.state = State::Suspend;
+                            return None;
+                        }
+                    }
+                    let interp = self.interpolate();
+                    self.pos += self.numer;
+                    return Some(interp);
+                }
+                State::Suspend => {
+                    if let Some(s) = iter.next() {
+                        self.last_in[N - 1] = s;
+                        self.state = State::Normal;
+                    } else {
+                        return None;
+                    }
+                }
+            }
+        }
+    }
+}
+
+pub struct Manager<K, const N: usize> {
+    ratio: Rational64,
+    kernel: PhantomData<fn() -> K>,
+}
+
+impl<K, const N: usize> Clone for Manager<K, N> {
+    #[inline]
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<K, const N: usize> Copy for Manager<K, N> {}
+
+impl<K: Kernel<N>, const N: usize> Manager<K, N> {
+    #[inline]
+    pub fn new<R>(ratio: R) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        let ratio = ratio.try_into()?.into();
+        check_supported(ratio)?;
+        Ok(Self {
+            ratio,
+            kernel: PhantomData,
+        })
+    }
+
+    #[inline]
+    pub fn converter(&self) -> Converter<N> {
+        Converter::new(self.ratio.recip(), K::weights)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Nearest;
+
+    impl Kernel<2> for Nearest {
+        fn weights(x: f64) -> [f64; 2] {
+            if x < 0.5 {
+                [1.0, 0.0]
+            } else {
+                [0.0, 1.0]
+            }
+        }
+    }
+
+    #[test]
+    fn test_manager_ok() {
+        let ratio_ok = vec![0.0625, 0.063, 1.0, 15.9, 16.0];
+        for ratio in ratio_ok {
+            assert!(Manager::<Nearest, 2>::new(ratio).is_ok());
+        }
+    }
+
+    #[test]
+    fn test_manager_err() {
+        let ratio_err = vec![-1.0, 0.0, 0.0624, 16.01, 0.123456, f64::NAN];
+        for ratio in ratio_err {
+            assert!(Manager::<Nearest, 2>::new(ratio).is_err());
+        }
+    }
+
+    #[test]
+    fn test_kernel() {
+        let samples = vec![1.0, 2.0, 3.0, 4.0];
+        let mut converter = Manager::<Nearest, 2>::new(2.0).unwrap().converter();
+        let output: Vec<f64> = converter.process(samples.into_iter()).collect();
+        assert_eq!(output, vec![1.0, 1.0, 2.0, 2.0, 3.0, 3.0, 4.0]);
+    }
+}
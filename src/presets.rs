@@ -0,0 +1,55 @@
+//! Filter tables embedded at build time, with the `presets` feature.
+
+use alloc::vec::Vec;
+
+struct Preset {
+    quan: u32,
+    order: u32,
+    kaiser_beta: u64,
+    cutoff: u64,
+    // little-endian f64
+    table: &'static [u8],
+}
+
+include!(concat!(env!("OUT_DIR"), "/presets.rs"));
+
+/// Find the embedded table with exactly the same parameters.
+pub(crate) fn find(quan: u32, order: u32, kaiser_beta: f64, cutoff: f64) -> Option<Vec<f64>> {
+    let preset = PRESETS.iter().find(|p| {
+        p.quan == quan
+            && p.order == order
+            && p.kaiser_beta == kaiser_beta.to_bits()
+            && p.cutoff == cutoff.to_bits()
+    })?;
+    let table = preset
+        .table
+        .chunks_exact(8)
+        .map(|b| f64::from_le_bytes(b.try_into().unwrap()))
+        .collect();
+    Some(table)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::kaiser::fill_filter_table;
+    use crate::sinc;
+
+    #[test]
+    fn test_presets() {
+        for (atten, quan) in [(96.0, 128), (108.0, 256), (120.0, 512)] {
+            for ratio in [2.0, 0.91875, 0.5] {
+                let manager = sinc::Manager::new(ratio, atten, quan, 0.1).unwrap();
+                let config = manager.config();
+                let (beta, cutoff) = (config.kaiser_beta.unwrap(), config.cutoff.unwrap());
+                let table = find(quan, manager.order(), beta, cutoff).unwrap();
+                let mut expected = vec![0.0; table.len()];
+                let len = table.len() - 1;
+                fill_filter_table(&mut expected[..len], 0, quan, manager.order(), beta, cutoff);
+                assert_eq!(table, expected);
+                assert_eq!(*manager.filter, expected);
+            }
+        }
+        assert!(find(128, 1, 0.0, 0.5).is_none());
+    }
+}
@@ -0,0 +1,262 @@
+//! Exact conversion ratio
+//!
+//! All the managers accept a [Ratio], or anything that converts into it, such
+//! as `f64`. Converting a float looks for the closest fraction, use
+//! [Ratio::approximate] to control how close it should be.
+//!
+//! ```
+//! use simple_src::{linear, Ratio};
+//!
+//! let ratio = Ratio::from_sample_rate(48000, 44100).unwrap();
+//! assert_eq!((ratio.numer(), ratio.denom()), (147, 160));
+//! let manager = linear::Manager::new(ratio).unwrap();
+//! let ratio = Ratio::approximate(0.91875, 1e-9).unwrap();
+//! assert_eq!((ratio.numer(), ratio.denom()), (147, 160));
+//! ```
+
+use num_rational::Rational64;
+
+use crate::math;
+
+use super::{check_range, Error, Result};
+
+/// Max numerator after reduction supported by the converters.
+pub(crate) const MAX_NUMER: i64 = 1024;
+
+/// Max ratio supported by the converters, and its reciprocal is the min.
+pub(crate) const MAX_RATIO: i64 = 16;
+
+/// Why a ratio is rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RatioError {
+    /// NaN, infinity or zero denominator.
+    NotFinite,
+    /// Zero or negative.
+    NotPositive,
+    /// Less than 1/16.
+    TooSmall,
+    /// Greater than 16.
+    TooLarge,
+    /// The numerator after reduction is greater than 1024.
+    NumeratorTooLarge,
+    /// No fraction found within the tolerance.
+    NoApproximation,
+}
+
+impl core::fmt::Display for RatioError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        let reason = match self {
+            Self::NotFinite => "not finite",
+            Self::NotPositive => "not positive",
+            Self::TooSmall => "less than 1/16",
+            Self::TooLarge => "greater than 16",
+            Self::NumeratorTooLarge => "numerator after reduction greater than 1024",
+            Self::NoApproximation => "no fraction within the tolerance",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl core::error::Error for RatioError {}
+
+/// A positive conversion ratio, fs_new / fs_old, kept as a reduced fraction.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Ratio(Rational64);
+
+impl Ratio {
+    /// Create a `Ratio` of `numer / denom`.
+    pub fn new(numer: i64, denom: i64) -> Result<Self> {
+        if denom == 0 {
+            return Err(Error::UnsupportedRatio(RatioError::NotFinite));
+        }
+        // reducing a negative fraction may overflow, e.g. with i64::MIN
+        if numer <= 0 || denom <= 0 {
+            return Err(Error::UnsupportedRatio(RatioError::NotPositive));
+        }
+        Rational64::new(numer, denom).try_into()
+    }
+
+    /// Create a `Ratio` of `new_sr / old_sr`.
+    pub fn from_sample_rate(old_sr: u32, new_sr: u32) -> Result<Self> {
+        Self::new(new_sr.into(), old_sr.into())
+    }
+
+    /// Find the simplest fraction within `tolerance` of `value`.
+    ///
+    /// The tolerance should be finite and not negative.
+    pub fn approximate(value: f64, tolerance: f64) -> Result<Self> {
+        check_range("tolerance", tolerance, 0.0, f64::MAX)?;
+        check_float(value)?;
+        // convergents of the continued fraction
+        let (mut h0, mut h1) = (0i64, 1i64);
+        let (mut k0, mut k1) = (1i64, 0i64);
+        let mut x = value;
+        loop {
+            let a = math::floor(x);
+            if a > i64::MAX as f64 {
+                break;
+            }
+            let a = a as i64;
+            let (Some(h), Some(k)) = (
+                a.checked_mul(h1).and_then(|v| v.checked_add(h0)),
+                a.checked_mul(k1).and_then(|v| v.checked_add(k0)),
+            ) else {
+                break;
+            };
+            (h0, h1, k0, k1) = (h1, h, k1, k);
+            if h > 0 && math::abs(h as f64 / k as f64 - value) <= tolerance {
+                return Self::new(h, k);
+            }
+            let frac = x - a as f64;
+            if frac == 0.0 {
+                break;
+            }
+            x = frac.recip();
+        }
+        Err(Error::UnsupportedRatio(RatioError::NoApproximation))
+    }
+
+    #[inline]
+    pub fn numer(&self) -> i64 {
+        *self.0.numer()
+    }
+
+    #[inline]
+    pub fn denom(&self) -> i64 {
+        *self.0.denom()
+    }
+
+    #[inline]
+    pub fn to_f64(&self) -> f64 {
+        self.numer() as f64 / self.denom() as f64
+    }
+}
+
+fn check_float(value: f64) -> Result<()> {
+    if !value.is_finite() {
+        Err(Error::UnsupportedRatio(RatioError::NotFinite))
+    } else if value <= 0.0 {
+        Err(Error::UnsupportedRatio(RatioError::NotPositive))
+    } else {
+        Ok(())
+    }
+}
+
+/// Check if the converters support the ratio.
+pub(crate) fn check_supported(ratio: Rational64) -> Result<()> {
+    let reason = if ratio <= Rational64::default() {
+        RatioError::NotPositive
+    } else if ratio < Rational64::new(1, MAX_RATIO) {
+        RatioError::TooSmall
+    } else if ratio > Rational64::from_integer(MAX_RATIO) {
+        RatioError::TooLarge
+    } else if *ratio.numer() > MAX_NUMER {
+        RatioError::NumeratorTooLarge
+    } else {
+        return Ok(());
+    };
+    Err(Error::UnsupportedRatio(reason))
+}
+
+impl TryFrom<Rational64> for Ratio {
+    type Error = Error;
+
+    fn try_from(value: Rational64) -> Result<Self> {
+        if value <= Rational64::default() {
+            return Err(Error::UnsupportedRatio(RatioError::NotPositive));
+        }
+        Ok(Self(value))
+    }
+}
+
+impl TryFrom<f64> for Ratio {
+    type Error = Error;
+
+    /// Find the closest fraction, may have a large numerator.
+    fn try_from(value: f64) -> Result<Self> {
+        check_float(value)?;
+        Rational64::approximate_float(value)
+            .ok_or(Error::UnsupportedRatio(RatioError::NoApproximation))?
+            .try_into()
+    }
+}
+
+impl From<Ratio> for Rational64 {
+    #[inline]
+    fn from(value: Ratio) -> Self {
+        value.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn reason(result: Result<Ratio>) -> RatioError {
+        match result {
+            Err(Error::UnsupportedRatio(reason)) => reason,
+            _ => panic!("not a ratio error"),
+        }
+    }
+
+    #[test]
+    fn test_new() {
+        let ratio = Ratio::new(44100, 48000).unwrap();
+        assert_eq!((ratio.numer(), ratio.denom()), (147, 160));
+        assert_eq!(reason(Ratio::new(1, 0)), RatioError::NotFinite);
+        assert_eq!(reason(Ratio::new(-1, 2)), RatioError::NotPositive);
+        assert_eq!(reason(Ratio::new(-1, -2)), RatioError::NotPositive);
+        assert_eq!(reason(Ratio::new(1, i64::MIN)), RatioError::NotPositive);
+        assert_eq!(
+            reason(Ratio::from_sample_rate(44100, 0)),
+            RatioError::NotPositive
+        );
+    }
+
+    #[test]
+    fn test_from_f64() {
+        let ratio = Ratio::try_from(0.5).unwrap();
+        assert_eq!((ratio.numer(), ratio.denom()), (1, 2));
+        assert_eq!(reason(f64::NAN.try_into()), RatioError::NotFinite);
+        assert_eq!(reason(f64::INFINITY.try_into()), RatioError::NotFinite);
+        assert_eq!(reason((-1.0).try_into()), RatioError::NotPositive);
+        assert_eq!(reason(0.0.try_into()), RatioError::NotPositive);
+    }
+
+    #[test]
+    fn test_approximate() {
+        let ratio = Ratio::approximate(std::f64::consts::PI, 2e-3).unwrap();
+        assert_eq!((ratio.numer(), ratio.denom()), (22, 7));
+        let ratio = Ratio::approximate(std::f64::consts::PI, 1e-6).unwrap();
+        assert_eq!((ratio.numer(), ratio.denom()), (355, 113));
+        let ratio = Ratio::approximate(2.0, 0.0).unwrap();
+        assert_eq!((ratio.numer(), ratio.denom()), (2, 1));
+        assert!(matches!(
+            Ratio::approximate(2.0, -1.0),
+            Err(Error::OutOfRange {
+                name: "tolerance",
+                ..
+            })
+        ));
+        assert_eq!(
+            reason(Ratio::approximate(f64::NAN, 1e-3)),
+            RatioError::NotFinite
+        );
+    }
+
+    #[test]
+    fn test_check_supported() {
+        let check = |n, d| match check_supported(Rational64::new(n, d)) {
+            Ok(()) => None,
+            Err(Error::UnsupportedRatio(reason)) => Some(reason),
+            _ => panic!("not a ratio error"),
+        };
+        assert_eq!(check(1, 16), None);
+        assert_eq!(check(16, 1), None);
+        assert_eq!(check(1024, 1023), None);
+        assert_eq!(check(1, 17), Some(RatioError::TooSmall));
+        assert_eq!(check(17, 1), Some(RatioError::TooLarge));
+        assert_eq!(check(1025, 1024), Some(RatioError::NumeratorTooLarge));
+        assert_eq!(check(0, 1), Some(RatioError::NotPositive));
+    }
+}
@@ -31,99 +31,120 @@
 //!     println!("{s}");
 //! }
 //! ```
+//!
+//! ## Presets
+//!
+//! With the `presets` feature, the tables of the 16bit presets in README are
+//! generated at build time and used when the parameters match exactly, that is
+//! `Manager::new(ratio, atten, quan, 0.1)` with `(atten, quan)` of `(96, 128)`,
+//! `(108, 256)` or `(120, 512)`, and the ratio is 147/160, 1/2 or not less
+//! than 1. It adds about 2MB to the binary.
 
-use std::collections::VecDeque;
-use std::f64::consts::PI;
-use std::sync::Arc;
+use alloc::collections::VecDeque;
+use alloc::sync::Arc;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::f64::consts::PI;
 
 use num_rational::Rational64;
 
-use crate::supported_ratio;
+use crate::config::{Algorithm, Config};
+use crate::kaiser::{calc_kaiser_beta, calc_order, calc_trans_width, fill_filter_table};
+use crate::math;
+use crate::ratio::{check_supported, Ratio};
 
-use super::{Convert, Error, Result};
+use super::{check_range, Convert, Cost, Error, Result};
 
-#[inline]
-fn sinc_c(x: f64, cutoff: f64) -> f64 {
-    if x != 0.0 {
-        (PI * x * cutoff).sin() / (PI * x)
-    } else {
-        cutoff
-    }
-}
+/// Min number of entries for each thread.
+#[cfg(feature = "parallel")]
+const MIN_CHUNK: usize = 1 << 15;
 
 #[inline]
-fn bessel_i0(x: f64) -> f64 {
-    let mut y = 1.0;
-    let mut t = 1.0;
-    for k in 1..32 {
-        t *= (x / (2.0 * k as f64)).powi(2);
-        y += t;
-        if t < 1e-10 {
-            break;
+fn generate_filter_table(quan: u32, order: u32, beta: f64, cutoff: f64) -> Vec<f64> {
+    let len = (order * quan / 2) as usize;
+    let mut filter = vec![0.0; len + 1];
+    #[cfg(feature = "parallel")]
+    {
+        let threads = std::thread::available_parallelism().map_or(1, |n| n.get());
+        let chunk = len.div_ceil(threads).max(MIN_CHUNK);
+        if chunk < len {
+            std::thread::scope(|s| {
+                for (i, part) in filter[..len].chunks_mut(chunk).enumerate() {
+                    s.spawn(move || fill_filter_table(part, i * chunk, quan, order, beta, cutoff));
+                }
+            });
+            return filter;
         }
     }
-    y
-}
-
-#[inline]
-#[allow(dead_code)]
-fn kaiser(x: f64, order: u32, beta: f64) -> f64 {
-    let half = order as f64 * 0.5;
-    if (x < -half) || (x > half) {
-        return 0.0;
-    }
-    bessel_i0(beta * (1.0 - (x / half).powi(2)).sqrt()) / bessel_i0(beta)
+    fill_filter_table(&mut filter[..len], 0, quan, order, beta, cutoff);
+    filter
 }
 
+/// Get the filter table from the [cache](crate::cache) if enabled, or the
+/// embedded presets before generating it.
 #[inline]
-fn generate_filter_table(quan: u32, order: u32, beta: f64, cutoff: f64) -> Vec<f64> {
-    let len = order * quan / 2;
-    let i0_beta = bessel_i0(beta);
-    let half_order = order as f64 * 0.5;
-    let mut filter = Vec::with_capacity(len as usize + 1);
-    for i in 0..len {
-        let pos = i as f64 / quan as f64;
-        let i0_1 = bessel_i0(beta * (1.0 - (pos / half_order).powi(2)).sqrt());
-        let coef = sinc_c(pos, cutoff) * (i0_1 / i0_beta);
-        filter.push(coef);
-    }
-    filter.push(0.0);
-    filter
+fn filter_table(quan: u32, order: u32, beta: f64, cutoff: f64) -> Arc<Vec<f64>> {
+    let generate = || {
+        #[cfg(feature = "presets")]
+        if let Some(table) = crate::presets::find(quan, order, beta, cutoff) {
+            return table;
+        }
+        generate_filter_table(quan, order, beta, cutoff)
+    };
+    #[cfg(feature = "std")]
+    return crate::cache::get_or_insert(quan, order, beta, cutoff, generate);
+    #[cfg(not(feature = "std"))]
+    Arc::new(generate())
 }
 
+/// Look up the filter table at `pos`, which is `|x| * quan`, with linear
+/// interpolation between the entries, 0 outside the table.
 #[inline]
-fn calc_kaiser_beta(atten: f64) -> f64 {
-    if atten > 50.0 {
-        0.1102 * (atten - 8.7)
-    } else if atten >= 21.0 {
-        0.5842 * (atten - 21.0).powf(0.4) + 0.07886 * (atten - 21.0)
+fn lookup(filter: &[f64], pos: f64) -> f64 {
+    let posu = pos as usize;
+    if posu < filter.len() - 1 {
+        let h1 = filter[posu];
+        let h2 = filter[posu + 1];
+        h1 + (h2 - h1) * (pos - posu as f64)
     } else {
         0.0
     }
 }
 
-#[inline]
-fn calc_trans_width(ratio: f64, atten: f64, order: u32) -> f64 {
-    (atten - 8.0) / (2.285 * order as f64 * PI * ratio.min(1.0))
-}
-
-#[inline]
-fn calc_order(ratio: f64, atten: f64, trans_width: f64) -> u32 {
-    f64::ceil((atten - 8.0) / (2.285 * trans_width * PI * ratio.min(1.0))) as u32
-}
-
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum State {
     Normal,
     Suspend,
 }
 
+/// The state of a [Converter], see [Converter::snapshot].
+#[derive(Clone, Debug, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Snapshot {
+    numer: usize,
+    denom: usize,
+    order: u32,
+    quan: u32,
+    kaiser_beta: f64,
+    cutoff: f64,
+    pos: usize,
+    offset: f64,
+    buf: Vec<f64>,
+    state: State,
+}
+
 pub struct Converter {
     numer: usize,
     denom: usize,
     pos: usize,
+    // phase below the resolution of pos, in input samples
+    offset: f64,
     coefs: Vec<f64>,
     half_order: f64,
     quan: f64,
+    kaiser_beta: f64,
+    cutoff: f64,
     filter: Arc<Vec<f64>>,
     buf: VecDeque<f64>,
     state: State,
@@ -131,45 +152,87 @@ pub struct Converter {
 
 impl Converter {
     #[inline]
-    fn new(step: Rational64, order: u32, quan: u32, filter: Arc<Vec<f64>>) -> Self {
+    fn new(manager: &Manager, phase: f64) -> Self {
+        let step = manager.ratio.recip();
+        let order = manager.order;
         let numer = *step.numer() as usize;
         let denom = *step.denom() as usize;
+        // advance by phase * step input samples, pos is in 1 / denom
+        let advance = phase * numer as f64;
+        let pos = math::floor(advance);
         let mut coefs = Vec::with_capacity(denom);
         for i in 0..denom {
             coefs.push(i as f64 / denom as f64);
         }
         let taps = (order + 1) as usize;
         let mut buf = VecDeque::with_capacity(taps);
-        buf.extend(std::iter::repeat(0.0).take(taps));
+        buf.extend(core::iter::repeat_n(0.0, taps));
         Self {
             numer,
             denom,
-            pos: 0,
+            pos: pos as usize,
+            offset: (advance - pos) / denom as f64,
             coefs,
             half_order: 0.5 * order as f64,
-            quan: quan as f64,
-            filter,
+            quan: manager.quan as f64,
+            kaiser_beta: manager.kaiser_beta,
+            cutoff: manager.cutoff,
+            filter: manager.filter.clone(),
             buf,
             state: State::Normal,
         }
     }
 
+    /// Take a snapshot of the position and the history, which can be restored
+    /// to a `Converter` of the same `Manager` parameters.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            numer: self.numer,
+            denom: self.denom,
+            order: (self.buf.len() - 1) as u32,
+            quan: self.quan as u32,
+            kaiser_beta: self.kaiser_beta,
+            cutoff: self.cutoff,
+            pos: self.pos,
+            offset: self.offset,
+            buf: self.buf.iter().copied().collect(),
+            state: self.state,
+        }
+    }
+
+    /// Restore a snapshot, it fails if the ratio or any parameter of the filter
+    /// is different, or the snapshot is corrupted.
+    pub fn restore(&mut self, snapshot: &Snapshot) -> Result<()> {
+        if snapshot.numer != self.numer
+            || snapshot.denom != self.denom
+            || snapshot.order as usize != self.buf.len() - 1
+            || snapshot.quan as f64 != self.quan
+            || snapshot.kaiser_beta != self.kaiser_beta
+            || snapshot.cutoff != self.cutoff
+            || snapshot.buf.len() != self.buf.len()
+            || snapshot.pos >= self.denom + self.numer
+            || !(0.0..1.0 / self.denom as f64).contains(&snapshot.offset)
+        {
+            return Err(Error::SnapshotMismatch);
+        }
+        self.pos = snapshot.pos;
+        self.offset = snapshot.offset;
+        self.buf.clear();
+        self.buf.extend(&snapshot.buf);
+        self.state = snapshot.state;
+        Ok(())
+    }
+
     #[inline]
     fn interpolate(&self) -> f64 {
-        let coef = self.coefs[self.pos];
+        let coef = self.coefs[self.pos] + self.offset;
         let mut interp = 0.0;
-        let pos_max = self.filter.len() - 1;
         let taps = self.buf.len();
         let iter_count = taps / 2;
         let mut left;
         let mut right;
         if taps % 2 == 1 {
-            let pos = coef * self.quan;
-            let posu = pos as usize;
-            let h1 = self.filter[posu];
-            let h2 = self.filter[posu + 1];
-            let h = h1 + (h2 - h1) * (pos - posu as f64);
-            interp += self.buf[iter_count] * h;
+            interp += self.buf[iter_count] * lookup(&self.filter, coef * self.quan);
             left = iter_count - 1;
             right = iter_count + 1;
         } else {
@@ -178,22 +241,10 @@ impl Converter {
         }
         let coef = coef + self.half_order;
         for _ in 0..iter_count {
-            let pos1 = (coef - left as f64).abs() * self.quan;
-            let pos2 = (coef - right as f64).abs() * self.quan;
-            let pos1u = pos1 as usize;
-            let pos2u = pos2 as usize;
-            if pos1u < pos_max {
-                let h1 = self.filter[pos1u];
-                let h2 = self.filter[pos1u + 1];
-                let h = h1 + (h2 - h1) * (pos1 - pos1u as f64);
-                interp += self.buf[left] * h;
-            }
-            if pos2u < pos_max {
-                let h1 = self.filter[pos2u];
-                let h2 = self.filter[pos2u + 1];
-                let h = h1 + (h2 - h1) * (pos2 - pos2u as f64);
-                interp += self.buf[right] * h;
-            }
+            let pos1 = math::abs(coef - left as f64) * self.quan;
+            let pos2 = math::abs(coef - right as f64) * self.quan;
+            interp += self.buf[left] * lookup(&self.filter, pos1);
+            interp += self.buf[right] * lookup(&self.filter, pos2);
             left = left.wrapping_sub(1);
             right = right.wrapping_add(1);
         }
@@ -238,83 +289,300 @@ impl Convert for Converter {
     }
 }
 
+/// A delay line with fractional delay in samples, created by
+/// [Manager::fractional_delay].
+///
+/// The delay can be changed at any time, e.g. for chorus or flanger effects.
+pub struct FractionalDelay {
+    half_order: f64,
+    quan: f64,
+    filter: Arc<Vec<f64>>,
+    buf: VecDeque<f64>,
+    delay: f64,
+    max_delay: f64,
+}
+
+impl FractionalDelay {
+    #[inline]
+    fn new(order: u32, quan: u32, filter: Arc<Vec<f64>>, max_delay: usize) -> Self {
+        let half_order = 0.5 * order as f64;
+        let max_delay = (max_delay as f64).max(half_order);
+        let len = math::ceil(max_delay + half_order) as usize + 1;
+        Self {
+            half_order,
+            quan: quan as f64,
+            filter,
+            buf: VecDeque::from(vec![0.0; len]),
+            delay: half_order,
+            max_delay,
+        }
+    }
+
+    /// Set the delay in samples, clamped to `[min_delay, max_delay]`.
+    #[inline]
+    pub fn set_delay(&mut self, delay: f64) {
+        self.delay = delay.clamp(self.half_order, self.max_delay);
+    }
+
+    /// Get the current delay in samples.
+    #[inline]
+    pub fn delay(&self) -> f64 {
+        self.delay
+    }
+
+    /// Get the min delay, which is half of the order of the FIR filter.
+    #[inline]
+    pub fn min_delay(&self) -> f64 {
+        self.half_order
+    }
+
+    /// Get the max delay.
+    #[inline]
+    pub fn max_delay(&self) -> f64 {
+        self.max_delay
+    }
+
+    /// Push a sample and get the delayed output.
+    #[inline]
+    pub fn process_sample(&mut self, sample: f64) -> f64 {
+        self.buf.pop_front();
+        self.buf.push_back(sample);
+        let center = (self.buf.len() - 1) as f64 - self.delay;
+        let first = math::ceil(center - self.half_order) as usize;
+        let last = math::floor(center + self.half_order) as usize;
+        let mut interp = 0.0;
+        for i in first..=last.min(self.buf.len() - 1) {
+            let pos = math::abs(center - i as f64) * self.quan;
+            interp += self.buf[i] * lookup(&self.filter, pos);
+        }
+        interp
+    }
+}
+
+impl Convert for FractionalDelay {
+    #[inline]
+    fn next_sample<I>(&mut self, iter: &mut I) -> Option<f64>
+    where
+        I: Iterator<Item = f64>,
+    {
+        iter.next().map(|s| self.process_sample(s))
+    }
+}
+
+/// How the signal is extended beyond its edges in [Manager::evaluate_at_with].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Padding {
+    /// The samples outside are 0.
+    #[default]
+    Zero,
+    /// Repeat the first or the last sample.
+    Clamp,
+    /// Mirror at the first and the last sample.
+    Reflect,
+    /// Treat the signal as periodic.
+    Wrap,
+}
+
+impl Padding {
+    /// Map the index to the signal of length `len`, `None` for a zero sample.
+    #[inline]
+    fn index(self, i: i64, len: usize) -> Option<usize> {
+        let n = len as i64;
+        if (0..n).contains(&i) {
+            return Some(i as usize);
+        }
+        match self {
+            Padding::Zero => None,
+            Padding::Clamp => Some(i.clamp(0, n - 1) as usize),
+            Padding::Reflect if n == 1 => Some(0),
+            Padding::Reflect => {
+                let period = 2 * (n - 1);
+                let i = i.rem_euclid(period);
+                Some(if i < n { i } else { period - i } as usize)
+            }
+            Padding::Wrap => Some(i.rem_euclid(n) as usize),
+        }
+    }
+}
+
 const MIN_ORDER: u32 = 1;
-const MAX_ORDER: u32 = 2048;
-const MIN_QUAN: u32 = 1;
-const MAX_QUAN: u32 = 16384;
-const MIN_ATTEN: f64 = 12.0;
-const MAX_ATTEN: f64 = 180.0;
+pub(crate) const MAX_ORDER: u32 = 2048;
+pub(crate) const MIN_QUAN: u32 = 1;
+pub(crate) const MAX_QUAN: u32 = 16384;
+pub(crate) const MIN_ATTEN: f64 = 12.0;
+pub(crate) const MAX_ATTEN: f64 = 180.0;
 
-#[derive(Clone)]
-pub struct Manager {
+/// Calculate order, kaiser beta and cutoff with attenuation and transition band
+/// width.
+fn design_with_trans_width(
+    ratio: Rational64,
+    atten: f64,
+    trans_width: f64,
+) -> Result<(u32, f64, f64)> {
+    check_range("atten", atten, MIN_ATTEN, MAX_ATTEN)?;
+    check_range("trans_width", trans_width, 0.01, 1.0)?;
+    let kaiser_beta = calc_kaiser_beta(atten);
+    let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+    let order = calc_order(fratio, atten, trans_width);
+    let cutoff = fratio.min(1.0) * (1.0 - 0.5 * trans_width);
+    Ok((order, kaiser_beta, cutoff))
+}
+
+/// Calculate kaiser beta and cutoff with attenuation and order.
+fn design_with_order(ratio: Rational64, atten: f64, order: u32) -> Result<(u32, f64, f64)> {
+    check_range("atten", atten, MIN_ATTEN, MAX_ATTEN)?;
+    check_range("order", order, MIN_ORDER, MAX_ORDER)?;
+    let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+    let kaiser_beta = calc_kaiser_beta(atten);
+    let trans_width = calc_trans_width(fratio, atten, order);
+    let cutoff = fratio.min(1.0) * (1.0 - 0.5 * trans_width);
+    Ok((order, kaiser_beta, cutoff))
+}
+
+/// Calculate the transition band width with the pass band frequency.
+pub(crate) fn pass_freq_trans_width(old_sr: u32, new_sr: u32, pass_freq: u32) -> Result<f64> {
+    let min_sr = new_sr.min(old_sr);
+    check_range("pass_freq", pass_freq, 0, (min_sr as f64 * 0.495) as u32)?;
+    Ok(min_sr.saturating_sub(pass_freq.saturating_mul(2)) as f64 / min_sr as f64)
+}
+
+/// Multiply-adds per output sample of the order.
+#[inline]
+fn mul_adds(order: u32) -> f64 {
+    2.0 * (order as f64 + 1.0)
+}
+
+/// The max order whose multiply-adds are not more than `max`, at least 1.
+#[inline]
+fn max_order_of(max: f64) -> u32 {
+    (math::floor(max * 0.5) - 1.0).clamp(MIN_ORDER as f64, MAX_ORDER as f64) as u32
+}
+
+/// Lower the attenuation if the order calculated with it is more than
+/// `max_order`, but not less than the min.
+fn fit_atten(ratio: Rational64, atten: f64, trans_width: f64, max_order: Option<u32>) -> f64 {
+    let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+    match max_order {
+        Some(max) if calc_order(fratio, atten, trans_width) > max => {
+            // inverse of calc_order, a bit less to avoid rounding up
+            let fit = 8.0 + 2.285 * (max as f64 - 1e-6) * trans_width * PI * fratio.min(1.0);
+            fit.clamp(MIN_ATTEN, atten)
+        }
+        _ => atten,
+    }
+}
+
+/// Latency in output samples of the order.
+#[inline]
+fn latency_of(ratio: Rational64, order: u32) -> usize {
+    let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+    math::round(fratio * order as f64 * 0.5) as usize
+}
+
+/// The max order whose latency is not more than `max`, at least 1.
+fn max_order_of_latency(ratio: Rational64, max: usize) -> u32 {
+    let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+    let mut order = math::floor(2.0 * (max as f64 + 1.0) / fratio)
+        .clamp(MIN_ORDER as f64, MAX_ORDER as f64) as u32;
+    while order > MIN_ORDER && latency_of(ratio, order) > max {
+        order -= 1;
+    }
+    order
+}
+
+/// Size of the filter table in bytes.
+#[inline]
+pub(crate) fn table_bytes(quan: u32, order: u32) -> usize {
+    (order as usize * quan as usize / 2 + 1) * size_of::<f64>()
+}
+
+/// The largest power of 2 quantify whose table fits in `max_bytes`, not more
+/// than the one recommended for `atten`, that is `2 ^ (A / 12 - 1)`.
+fn fit_quan(atten: f64, order: u32, max_bytes: usize) -> u32 {
+    let exp = math::ceil(atten / 12.0 - 1.0).clamp(0.0, MAX_QUAN.ilog2() as f64);
+    let mut quan = 1 << exp as u32;
+    while quan > MIN_QUAN && table_bytes(quan, order) > max_bytes {
+        quan /= 2;
+    }
+    quan
+}
+
+/// Check the raw parameters of a `Manager`.
+pub(crate) fn check_raw(
     ratio: Rational64,
-    order: u32,
     quan: u32,
+    order: u32,
+    kaiser_beta: f64,
+    cutoff: f64,
+) -> Result<()> {
+    check_supported(ratio)?;
+    check_range("quan", quan, MIN_QUAN, MAX_QUAN)?;
+    check_range("order", order, MIN_ORDER, MAX_ORDER)?;
+    check_range("kaiser_beta", kaiser_beta, 0.0, 20.0)?;
+    check_range("cutoff", cutoff, 0.01, 1.0)
+}
+
+#[derive(Clone)]
+pub struct Manager {
+    pub(crate) ratio: Rational64,
+    pub(crate) order: u32,
+    pub(crate) quan: u32,
+    pub(crate) kaiser_beta: f64,
+    pub(crate) cutoff: f64,
     latency: usize,
-    filter: Arc<Vec<f64>>,
+    pub(crate) filter: Arc<Vec<f64>>,
 }
 
 impl Manager {
-    fn with_raw_internal(
+    pub(crate) fn with_raw_internal(
         ratio: Rational64,
         quan: u32,
         order: u32,
         kaiser_beta: f64,
         cutoff: f64,
     ) -> Result<Self> {
-        if !supported_ratio(ratio) {
-            return Err(Error::UnsupportedRatio);
-        }
-        if !(MIN_QUAN..=MAX_QUAN).contains(&quan)
-            || !(MIN_ORDER..=MAX_ORDER).contains(&order)
-            || !(0.0..=20.0).contains(&kaiser_beta)
-            || !(0.01..=1.0).contains(&cutoff)
-        {
-            return Err(Error::InvalidParam);
-        }
-        let filter = generate_filter_table(quan, order, kaiser_beta, cutoff);
-        let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
-        let latency = (fratio * order as f64 * 0.5).round() as usize;
-        Ok(Self {
+        check_raw(ratio, quan, order, kaiser_beta, cutoff)?;
+        let filter = filter_table(quan, order, kaiser_beta, cutoff);
+        Ok(Self::from_parts(
+            ratio,
+            quan,
+            order,
+            kaiser_beta,
+            cutoff,
+            filter,
+        ))
+    }
+
+    /// Create a `Manager` with the checked parameters and their table.
+    pub(crate) fn from_parts(
+        ratio: Rational64,
+        quan: u32,
+        order: u32,
+        kaiser_beta: f64,
+        cutoff: f64,
+        filter: Arc<Vec<f64>>,
+    ) -> Self {
+        let latency = latency_of(ratio, order);
+        Self {
             ratio,
             order,
             quan,
+            kaiser_beta,
+            cutoff,
             latency,
-            filter: Arc::new(filter),
-        })
+            filter,
+        }
     }
 
     fn new_internal(ratio: Rational64, atten: f64, quan: u32, trans_width: f64) -> Result<Self> {
-        if !supported_ratio(ratio) {
-            return Err(Error::UnsupportedRatio);
-        }
-        if !(MIN_ATTEN..=MAX_ATTEN).contains(&atten)
-            || !(MIN_QUAN..=MAX_QUAN).contains(&quan)
-            || !(0.01..=1.0).contains(&trans_width)
-        {
-            return Err(Error::InvalidParam);
-        }
-        let kaiser_beta = calc_kaiser_beta(atten);
-        let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
-        let order = calc_order(fratio, atten, trans_width);
-        let cutoff = fratio.min(1.0) * (1.0 - 0.5 * trans_width);
+        check_supported(ratio)?;
+        let (order, kaiser_beta, cutoff) = design_with_trans_width(ratio, atten, trans_width)?;
         Self::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)
     }
 
     fn with_order_internal(ratio: Rational64, atten: f64, quan: u32, order: u32) -> Result<Self> {
-        if !supported_ratio(ratio) {
-            return Err(Error::UnsupportedRatio);
-        }
-        if !(MIN_ATTEN..=MAX_ATTEN).contains(&atten)
-            || !(MIN_QUAN..=MAX_QUAN).contains(&quan)
-            || !(MIN_ORDER..=MAX_ORDER).contains(&order)
-        {
-            return Err(Error::InvalidParam);
-        }
-        let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
-        let kaiser_beta = calc_kaiser_beta(atten);
-        let trans_width = calc_trans_width(fratio, atten, order);
-        let cutoff = fratio.min(1.0) * (1.0 - 0.5 * trans_width);
+        check_supported(ratio)?;
+        let (order, kaiser_beta, cutoff) = design_with_order(ratio, atten, order)?;
         Self::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)
     }
 
@@ -327,15 +595,18 @@ impl Manager {
     /// - order: the order of interpolation FIR filter, support `[1, 2048]`
     /// - kaiser_beta: the beta parameter of kaiser window method, support `[0.0, 20.0]`
     /// - cutoff: the cutoff of FIR filter, according to target sample rate, in `[0.01, 1.0]`
-    pub fn with_raw(
-        ratio: f64,
+    pub fn with_raw<R>(
+        ratio: R,
         quan: u32,
         order: u32,
         kaiser_beta: f64,
         cutoff: f64,
-    ) -> Result<Self> {
-        let ratio = Rational64::approximate_float(ratio).unwrap_or_default();
-        Self::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)
+    ) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::with_raw_internal(ratio.try_into()?.into(), quan, order, kaiser_beta, cutoff)
     }
 
     /// Create a `Manager` with attenuation, quantify and transition band width.
@@ -348,9 +619,12 @@ impl Manager {
     /// - quan: the quantify number, usually power of 2, support `[1, 16384]`
     /// - trans_width: the transition band width in `[0.01, 1.0]`
     #[inline]
-    pub fn new(ratio: f64, atten: f64, quan: u32, trans_width: f64) -> Result<Self> {
-        let ratio = Rational64::approximate_float(ratio).unwrap_or_default();
-        Self::new_internal(ratio, atten, quan, trans_width)
+    pub fn new<R>(ratio: R, atten: f64, quan: u32, trans_width: f64) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::new_internal(ratio.try_into()?.into(), atten, quan, trans_width)
     }
 
     /// Create a `Manager` with attenuation, quantify and order
@@ -362,9 +636,12 @@ impl Manager {
     /// - quan: `[1, 16384]`
     /// - order: `[1, 2048]`
     #[inline]
-    pub fn with_order(ratio: f64, atten: f64, quan: u32, order: u32) -> Result<Self> {
-        let ratio = Rational64::approximate_float(ratio).unwrap_or_default();
-        Self::with_order_internal(ratio, atten, quan, order)
+    pub fn with_order<R>(ratio: R, atten: f64, quan: u32, order: u32) -> Result<Self>
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        Self::with_order_internal(ratio.try_into()?.into(), atten, quan, order)
     }
 
     /// Create a `Manager` with sample rate, attenuation, quantify and pass frequency
@@ -385,46 +662,206 @@ impl Manager {
         quan: u32,
         pass_freq: u32,
     ) -> Result<Self> {
-        if old_sr == 0 || new_sr == 0 {
-            return Err(Error::InvalidParam);
-        }
-        let ratio = Rational64::new(new_sr.into(), old_sr.into());
-        if !supported_ratio(ratio) {
-            return Err(Error::UnsupportedRatio);
-        }
-        let min_sr = new_sr.min(old_sr);
-        let trans_width = min_sr.saturating_sub(pass_freq.saturating_mul(2)) as f64 / min_sr as f64;
+        let ratio = Ratio::from_sample_rate(old_sr, new_sr)?.into();
+        check_supported(ratio)?;
+        let trans_width = pass_freq_trans_width(old_sr, new_sr, pass_freq)?;
         Self::new_internal(ratio, atten, quan, trans_width)
     }
 
     /// Create a `Converter` which actually implement the interpolation.
     #[inline]
     pub fn converter(&self) -> Converter {
-        Converter::new(
-            self.ratio.recip(),
-            self.order,
-            self.quan,
-            self.filter.clone(),
-        )
+        self.converter_with_phase(0.0)
+    }
+
+    /// Create a `Converter` whose output is advanced by `phase` output samples
+    /// in `[0, 1)`, so its group delay is `group_delay() - phase`.
+    ///
+    /// To align the output of `b` with `a`, skip the integer part of the
+    /// difference of their group delays and advance the fractional part:
+    ///
+    /// ```
+    /// use simple_src::{sinc, Convert};
+    ///
+    /// let a = sinc::Manager::new(2.0, 96.0, 128, 0.1).unwrap();
+    /// let b = sinc::Manager::new(2.0, 120.0, 256, 0.1).unwrap();
+    /// let diff = b.group_delay() - a.group_delay();
+    /// let mut converter = b.converter_with_phase(diff.fract());
+    /// let samples = vec![1.0, 2.0, 3.0, 4.0];
+    /// for s in converter.process(samples.into_iter()).skip(diff as usize) {
+    ///     println!("{s}");
+    /// }
+    /// ```
+    #[inline]
+    pub fn converter_with_phase(&self, phase: f64) -> Converter {
+        Converter::new(self, phase.clamp(0.0, 1.0 - f64::EPSILON))
+    }
+
+    /// Create a `FractionalDelay` with the same filter, the initial delay is
+    /// the min delay.
+    ///
+    /// Usually the ratio of the `Manager` is 1, a ratio less than 1 also
+    /// lowers the cutoff of the delay line.
+    ///
+    /// ```
+    /// use simple_src::sinc;
+    ///
+    /// let manager = sinc::Manager::new(1.0, 96.0, 128, 0.1).unwrap();
+    /// let mut delay = manager.fractional_delay(1000);
+    /// delay.set_delay(440.5);
+    /// let output: Vec<f64> = (0..2000).map(|i| delay.process_sample(i as f64)).collect();
+    /// ```
+    #[inline]
+    pub fn fractional_delay(&self, max_delay: usize) -> FractionalDelay {
+        FractionalDelay::new(self.order, self.quan, self.filter.clone(), max_delay)
+    }
+
+    /// Evaluate the band-limited `signal` at time `t` in samples, the samples
+    /// outside the signal are 0.
+    ///
+    /// ```
+    /// use simple_src::sinc;
+    ///
+    /// let manager = sinc::Manager::new(1.0, 96.0, 128, 0.1).unwrap();
+    /// let signal: Vec<f64> = (0..1000).map(|i| (i as f64 * 0.1).sin()).collect();
+    /// let s = manager.evaluate_at(&signal, 500.5);
+    /// assert!((s - (500.5f64 * 0.1).sin()).abs() < 1e-4);
+    /// ```
+    #[inline]
+    pub fn evaluate_at(&self, signal: &[f64], t: f64) -> f64 {
+        self.evaluate_at_with(signal, t, Padding::Zero)
+    }
+
+    /// Evaluate the band-limited `signal` at time `t` in samples, the signal
+    /// is extended by `padding` at the edges.
+    ///
+    /// Return NaN if `t` is not finite, and 0 if `signal` is empty.
+    pub fn evaluate_at_with(&self, signal: &[f64], t: f64, padding: Padding) -> f64 {
+        if !t.is_finite() {
+            return f64::NAN;
+        }
+        if signal.is_empty() {
+            return 0.0;
+        }
+        let half_order = 0.5 * self.order as f64;
+        let quan = self.quan as f64;
+        let first = math::ceil(t - half_order) as i64;
+        let last = math::floor(t + half_order) as i64;
+        let mut interp = 0.0;
+        for i in first..=last {
+            if let Some(index) = padding.index(i, signal.len()) {
+                let pos = math::abs(t - i as f64) * quan;
+                interp += signal[index] * lookup(&self.filter, pos);
+            }
+        }
+        interp
+    }
+
+    /// Evaluate the band-limited `signal` at each time of `times`, see
+    /// [Manager::evaluate_at_with].
+    pub fn evaluate_batch(&self, signal: &[f64], times: &[f64], padding: Padding) -> Vec<f64> {
+        times
+            .iter()
+            .map(|&t| self.evaluate_at_with(signal, t, padding))
+            .collect()
     }
 
     /// Get the latency of the FIR filter.
+    ///
+    /// It does not count the output taken before the first input sample is
+    /// consumed, see [Manager::group_delay] for the exact delay.
     #[inline]
     pub fn latency(&self) -> usize {
         self.latency
     }
 
+    /// Get the exact group delay in output samples, between an input sample
+    /// and its output, including the sample the converter emits before
+    /// pulling input.
+    #[inline]
+    pub fn group_delay(&self) -> f64 {
+        let fratio = *self.ratio.numer() as f64 / *self.ratio.denom() as f64;
+        fratio * self.input_group_delay()
+    }
+
+    /// Get the exact group delay in input samples, that is `order / 2 + 1`.
+    #[inline]
+    pub fn input_group_delay(&self) -> f64 {
+        self.order as f64 * 0.5 + 1.0
+    }
+
     /// Get the order of the FIR filter.
     #[inline]
     pub fn order(&self) -> u32 {
         self.order
     }
 
+    /// Get the size of the filter table in bytes.
+    #[inline]
+    pub fn table_bytes(&self) -> usize {
+        self.filter.len() * size_of::<f64>()
+    }
+
+    /// Get the transition band width, which is calculated by the builder or
+    /// `2 * (1 - cutoff / min(ratio, 1))` with raw parameters.
+    #[inline]
+    pub fn trans_width(&self) -> f64 {
+        let fratio = *self.ratio.numer() as f64 / *self.ratio.denom() as f64;
+        2.0 * (1.0 - self.cutoff / fratio.min(1.0))
+    }
+
+    /// Estimate the cost of a `Converter`, each tap takes a lookup with linear
+    /// interpolation and a multiply-add.
+    pub fn cost(&self) -> Cost {
+        let taps = self.order as usize + 1;
+        Cost {
+            mul_adds: mul_adds(self.order),
+            lookups: taps as f64,
+            history: taps,
+            memory: self.table_bytes()
+                + size_of::<Converter>()
+                + (taps + *self.ratio.numer() as usize) * size_of::<f64>(),
+        }
+    }
+
     /// Create a `Builder` to build `Manager`
     #[inline]
     pub fn builder() -> Builder {
         Builder::default()
     }
+
+    /// Create a `Manager` from a sinc [Config] with the combinations of
+    /// [Builder::build].
+    pub fn from_config(config: &Config) -> Result<Self> {
+        config.expect(Algorithm::Sinc)?;
+        let mut builder = Builder {
+            ratio: config.ratio.map(Ok),
+            order: config.order,
+            quan: config.quan,
+            kaiser_beta: config.kaiser_beta,
+            cutoff: config.cutoff,
+            atten: config.atten,
+            trans_width: config.trans_width,
+            ..Default::default()
+        };
+        if let (Some(old_sr), Some(new_sr)) = (config.old_sr, config.new_sr) {
+            builder = builder.sample_rate(old_sr, new_sr);
+        }
+        builder.pass_freq = config.pass_freq;
+        builder.build()
+    }
+
+    /// Get the raw parameters as a [Config], which builds the same `Manager`.
+    pub fn config(&self) -> Config {
+        Config {
+            ratio: Some(Ratio::try_from(self.ratio).unwrap()),
+            quan: Some(self.quan),
+            order: Some(self.order),
+            kaiser_beta: Some(self.kaiser_beta),
+            cutoff: Some(self.cutoff),
+            ..Config::new(Algorithm::Sinc)
+        }
+    }
 }
 
 /// The Builder to build `Manager`
@@ -442,7 +879,7 @@ impl Manager {
 /// ```
 #[derive(Default)]
 pub struct Builder {
-    ratio: Option<Rational64>,
+    ratio: Option<Result<Ratio>>,
     order: Option<u32>,
     quan: Option<u32>,
     kaiser_beta: Option<f64>,
@@ -452,12 +889,80 @@ pub struct Builder {
     old_sr: Option<u32>,
     new_sr: Option<u32>,
     pass_freq: Option<u32>,
+    max_table_bytes: Option<usize>,
+    max_cost: Option<f64>,
+    max_latency: Option<usize>,
+    strict: bool,
+}
+
+/// The combination of parameters used by [Builder::build].
+#[derive(Clone, Copy)]
+enum Combination {
+    Raw {
+        order: u32,
+        kaiser_beta: f64,
+        cutoff: f64,
+    },
+    TransWidth {
+        atten: f64,
+        trans_width: f64,
+    },
+    Order {
+        atten: f64,
+        order: u32,
+    },
+    PassFreq {
+        atten: f64,
+        old_sr: u32,
+        new_sr: u32,
+        pass_freq: u32,
+    },
+    Latency {
+        atten: f64,
+        max: usize,
+    },
+}
+
+/// A parameter set to the [Builder] but ignored, see [Builder::validate].
+///
+/// The names are the methods of the builder, `pass_width` is `trans_width`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Warning {
+    /// Not used by the combination.
+    Unused(&'static str),
+    /// Another parameter is used instead.
+    Overridden {
+        name: &'static str,
+        by: &'static str,
+    },
+}
+
+impl Warning {
+    /// The name of the ignored parameter.
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Unused(name) | Self::Overridden { name, .. } => name,
+        }
+    }
+}
+
+impl core::fmt::Display for Warning {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Unused(name) => write!(f, "`{name}` is not used"),
+            Self::Overridden { name, by } => write!(f, "`{name}` is overridden by `{by}`"),
+        }
+    }
 }
 
 impl Builder {
     /// Set `ratio`, in `[1/16, 16]`, the numerator after reduction should <= 1024
-    pub fn ratio(mut self, ratio: f64) -> Self {
-        self.ratio = Some(Rational64::approximate_float(ratio).unwrap_or_default());
+    pub fn ratio<R>(mut self, ratio: R) -> Self
+    where
+        R: TryInto<Ratio>,
+        Error: From<R::Error>,
+    {
+        self.ratio = Some(ratio.try_into().map_err(Error::from));
         self
     }
 
@@ -517,12 +1022,59 @@ impl Builder {
         self
     }
 
+    /// Set the max size of the filter table in bytes, building a larger one
+    /// fails with [Error::TableTooLarge].
+    ///
+    /// Without quantify, the largest power of 2 within the limit is chosen, but
+    /// not more than the one recommended for the attenuation, see README.
+    pub fn max_table_bytes(mut self, bytes: usize) -> Self {
+        self.max_table_bytes = Some(bytes);
+        self
+    }
+
+    /// Set the max multiply-adds per output sample, see [Manager::cost].
+    ///
+    /// With transition band width or pass frequency, the attenuation is
+    /// lowered to fit, with order the order is lowered, which widens the
+    /// transition band. Otherwise building fails with [Error::CostTooHigh].
+    pub fn max_cost(mut self, mul_adds: f64) -> Self {
+        self.max_cost = Some(mul_adds);
+        self
+    }
+
+    /// Set the max latency in output samples, see [Manager::latency].
+    ///
+    /// With attenuation only, the largest order within the limit is chosen,
+    /// see [Manager::trans_width] for the result. With order the order is
+    /// lowered. Otherwise building fails with [Error::LatencyTooHigh], also
+    /// when the attenuation cannot be met within the limit.
+    pub fn max_latency_samples(mut self, samples: usize) -> Self {
+        self.max_latency = Some(samples);
+        self
+    }
+
+    /// Set the max latency in milliseconds at the new sample rate `sr`, see
+    /// [max_latency_samples](Self::max_latency_samples).
+    pub fn max_latency_ms(self, ms: f64, sr: u32) -> Self {
+        self.max_latency_samples(math::floor(ms * sr as f64 / 1000.0) as usize)
+    }
+
+    /// In strict mode, building fails with [Error::IgnoredParam] if any
+    /// parameter is ignored, see [validate](Self::validate).
+    pub fn strict(mut self, strict: bool) -> Self {
+        self.strict = strict;
+        self
+    }
+
     /// Build the `Manager`, there are the following combinations in order:
     ///
     /// - ratio, quantify, order, kaiser_beta, cutoff
     /// - ratio, attenuation, quantify, trans_width or pass_width
     /// - ratio, attenuation, quantify, order
     /// - sample_rate, attenuation, quantify, pass_freq
+    /// - ratio, attenuation, quantify, max_latency
+    ///
+    /// The quantify can be omitted with attenuation and max_table_bytes.
     ///
     /// For example, this is the first situation:
     ///
@@ -539,20 +1091,122 @@ impl Builder {
     /// assert!(manager.is_ok());
     /// ```
     pub fn build(self) -> Result<Manager> {
-        let (ratio, quan) = match (self.ratio, self.quan, self.old_sr, self.new_sr) {
-            (Some(ratio), Some(quan), _, _) => (ratio, quan),
-            (_, Some(quan), Some(old_sr), Some(new_sr)) => {
-                if old_sr == 0 || new_sr == 0 {
-                    return Err(Error::InvalidParam);
+        let combination = self.combination()?;
+        if self.strict {
+            if let Some(warning) = self.warnings(combination).first() {
+                return Err(Error::IgnoredParam(warning.name()));
+            }
+        }
+        let ratio = match (self.ratio, self.old_sr, self.new_sr) {
+            (Some(ratio), _, _) => ratio?.into(),
+            (_, Some(old_sr), Some(new_sr)) => Ratio::from_sample_rate(old_sr, new_sr)?.into(),
+            _ => return Err(Error::NotEnoughParam),
+        };
+        check_supported(ratio)?;
+        let max_order = self.max_cost.map(max_order_of);
+        let latency_order = self.max_latency.map(|max| max_order_of_latency(ratio, max));
+        let (ratio, atten, (order, kaiser_beta, cutoff)) = match combination {
+            Combination::Raw {
+                order,
+                kaiser_beta,
+                cutoff,
+            } => (ratio, self.atten, (order, kaiser_beta, cutoff)),
+            Combination::TransWidth { atten, trans_width } => {
+                let atten = fit_atten(ratio, atten, trans_width, max_order);
+                let design = design_with_trans_width(ratio, atten, trans_width)?;
+                (ratio, Some(atten), design)
+            }
+            Combination::Order { atten, order } => {
+                let order = max_order.map_or(order, |max| order.min(max));
+                let order = latency_order.map_or(order, |max| order.min(max));
+                (ratio, Some(atten), design_with_order(ratio, atten, order)?)
+            }
+            Combination::PassFreq {
+                atten,
+                old_sr,
+                new_sr,
+                pass_freq,
+            } => {
+                let ratio = Ratio::from_sample_rate(old_sr, new_sr)?.into();
+                check_supported(ratio)?;
+                let trans_width = pass_freq_trans_width(old_sr, new_sr, pass_freq)?;
+                let atten = fit_atten(ratio, atten, trans_width, max_order);
+                let design = design_with_trans_width(ratio, atten, trans_width)?;
+                (ratio, Some(atten), design)
+            }
+            Combination::Latency { atten, max } => {
+                let order = max_order_of_latency(ratio, max);
+                let order = max_order.map_or(order, |max| order.min(max));
+                let fratio = *ratio.numer() as f64 / *ratio.denom() as f64;
+                if calc_trans_width(fratio, atten, order) > 1.0 {
+                    // the min order to meet the attenuation
+                    let min_order = calc_order(fratio, atten, 1.0);
+                    let latency = latency_of(ratio, min_order);
+                    return Err(Error::LatencyTooHigh { latency, max });
                 }
-                (Rational64::new(new_sr.into(), old_sr.into()), quan)
+                (ratio, Some(atten), design_with_order(ratio, atten, order)?)
             }
+        };
+        let quan = match (self.quan, atten, self.max_table_bytes) {
+            (Some(quan), _, _) => quan,
+            (None, Some(atten), Some(max)) => fit_quan(atten, order, max),
             _ => return Err(Error::NotEnoughParam),
         };
-        if !supported_ratio(ratio) {
-            return Err(Error::UnsupportedRatio);
+        check_raw(ratio, quan, order, kaiser_beta, cutoff)?;
+        if let Some(max) = self.max_cost {
+            if mul_adds(order) > max {
+                let mul_adds = mul_adds(order);
+                return Err(Error::CostTooHigh { mul_adds, max });
+            }
+        }
+        if let Some(max) = self.max_latency {
+            let latency = latency_of(ratio, order);
+            if latency > max {
+                return Err(Error::LatencyTooHigh { latency, max });
+            }
         }
-        match (
+        if let Some(max) = self.max_table_bytes {
+            let bytes = table_bytes(quan, order);
+            if bytes > max {
+                return Err(Error::TableTooLarge { bytes, max });
+            }
+        }
+        Manager::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)
+    }
+
+    /// Check the parameters without building, return the parameters which are
+    /// set but ignored, or the error known in advance.
+    ///
+    /// ```
+    /// use simple_src::sinc::{self, Warning};
+    ///
+    /// let builder = sinc::Manager::builder()
+    ///     .ratio(2.0)
+    ///     .attenuation(96)
+    ///     .quantify(128)
+    ///     .trans_width(0.1)
+    ///     .order(64);
+    /// let warnings = builder.validate().unwrap();
+    /// assert_eq!(warnings, [Warning::Overridden { name: "order", by: "trans_width" }]);
+    /// ```
+    pub fn validate(&self) -> Result<Vec<Warning>> {
+        match &self.ratio {
+            Some(Ok(ratio)) => check_supported((*ratio).into())?,
+            Some(Err(err)) => return Err(err.clone()),
+            None => {}
+        }
+        let combination = self.combination()?;
+        Ok(self.warnings(combination))
+    }
+
+    /// Find the combination used by [build](Self::build).
+    fn combination(&self) -> Result<Combination> {
+        let sample_rate = self.old_sr.is_some() && self.new_sr.is_some();
+        let quan = self.quan.is_some() || (self.atten.is_some() && self.max_table_bytes.is_some());
+        if !quan || (self.ratio.is_none() && !sample_rate) {
+            return Err(Error::NotEnoughParam);
+        }
+        let combination = match (
             self.order,
             self.kaiser_beta,
             self.cutoff,
@@ -561,21 +1215,84 @@ impl Builder {
             self.old_sr,
             self.new_sr,
             self.pass_freq,
+            self.max_latency,
         ) {
-            (Some(order), Some(kaiser_beta), Some(cutoff), _, _, _, _, _) => {
-                Manager::with_raw_internal(ratio, quan, order, kaiser_beta, cutoff)
+            (Some(order), Some(kaiser_beta), Some(cutoff), _, _, _, _, _, _) => Combination::Raw {
+                order,
+                kaiser_beta,
+                cutoff,
+            },
+            (_, _, _, Some(atten), Some(trans_width), _, _, _, _) => {
+                Combination::TransWidth { atten, trans_width }
+            }
+            (Some(order), _, _, Some(atten), _, _, _, _, _) => Combination::Order { atten, order },
+            (_, _, _, Some(atten), _, Some(old_sr), Some(new_sr), Some(pass_freq), _) => {
+                Combination::PassFreq {
+                    atten,
+                    old_sr,
+                    new_sr,
+                    pass_freq,
+                }
+            }
+            (None, _, _, Some(atten), None, _, _, None, Some(max)) => {
+                Combination::Latency { atten, max }
+            }
+            _ => return Err(Error::NotEnoughParam),
+        };
+        Ok(combination)
+    }
+
+    fn warnings(&self, combination: Combination) -> Vec<Warning> {
+        let mut warnings = Vec::new();
+        let sample_rate = self.old_sr.is_some() && self.new_sr.is_some();
+        let mut check = |set: bool, warning: Warning| {
+            if set {
+                warnings.push(warning);
+            }
+        };
+        let overridden = |name, by| Warning::Overridden { name, by };
+        if let Combination::PassFreq { .. } = combination {
+            check(self.ratio.is_some(), overridden("ratio", "sample_rate"));
+        } else {
+            check(
+                sample_rate && self.ratio.is_some(),
+                overridden("sample_rate", "ratio"),
+            );
+        }
+        let kaiser_beta = self.kaiser_beta.is_some();
+        let cutoff = self.cutoff.is_some();
+        let pass_freq = self.pass_freq.is_some();
+        match combination {
+            Combination::Raw { .. } => {
+                // attenuation only chooses the quantify
+                let atten = self.quan.is_some() || self.max_table_bytes.is_none();
+                check(
+                    self.atten.is_some() && atten,
+                    Warning::Unused("attenuation"),
+                );
+                check(
+                    self.trans_width.is_some(),
+                    overridden("trans_width", "order"),
+                );
+                check(pass_freq, overridden("pass_freq", "order"));
             }
-            (_, _, _, Some(atten), Some(trans_width), _, _, _) => {
-                Manager::new_internal(ratio, atten, quan, trans_width)
+            Combination::TransWidth { .. } => {
+                check(self.order.is_some(), overridden("order", "trans_width"));
+                check(kaiser_beta, Warning::Unused("kaiser_beta"));
+                check(cutoff, Warning::Unused("cutoff"));
+                check(pass_freq, overridden("pass_freq", "trans_width"));
             }
-            (Some(order), _, _, Some(atten), _, _, _, _) => {
-                Manager::with_order_internal(ratio, atten, quan, order)
+            Combination::Order { .. } => {
+                check(kaiser_beta, Warning::Unused("kaiser_beta"));
+                check(cutoff, Warning::Unused("cutoff"));
+                check(pass_freq, overridden("pass_freq", "order"));
             }
-            (_, _, _, Some(atten), _, Some(old_sr), Some(new_sr), Some(pass_freq)) => {
-                Manager::with_sample_rate(old_sr, new_sr, atten, quan, pass_freq)
+            Combination::PassFreq { .. } | Combination::Latency { .. } => {
+                check(kaiser_beta, Warning::Unused("kaiser_beta"));
+                check(cutoff, Warning::Unused("cutoff"));
             }
-            _ => Err(Error::NotEnoughParam),
         }
+        warnings
     }
 }
 
@@ -583,6 +1300,17 @@ impl Builder {
 mod tests {
     use super::*;
 
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn test_parallel_table() {
+        let (quan, order, beta, cutoff) = (1024, 256, 12.0, 0.9);
+        let table = generate_filter_table(quan, order, beta, cutoff);
+        let mut serial = vec![0.0; table.len()];
+        let len = serial.len() - 1;
+        fill_filter_table(&mut serial[..len], 0, quan, order, beta, cutoff);
+        assert_eq!(table, serial);
+    }
+
     #[test]
     fn test_manager_with_raw() {
         assert!(Manager::with_raw(2.0, 32, 32, 5.0, 0.8).is_ok());
@@ -613,6 +1341,114 @@ mod tests {
         assert!(Manager::with_order(2.0, 11.9, 32, 32).is_err());
     }
 
+    #[test]
+    fn test_fractional_delay() {
+        let manager = Manager::new(1.0, 120.0, 512, 0.2).unwrap();
+        let mut delay = manager.fractional_delay(100);
+        assert_eq!(delay.delay(), delay.min_delay());
+        delay.set_delay(1000.0);
+        assert_eq!(delay.delay(), 100.0);
+        delay.set_delay(60.3);
+        let omega = 0.25;
+        for i in 0..1000 {
+            let out = delay.process_sample((i as f64 * omega).sin());
+            if i > 200 {
+                let expected = ((i as f64 - 60.3) * omega).sin();
+                assert!((out - expected).abs() < 1e-5);
+            }
+        }
+    }
+
+    #[test]
+    fn test_evaluate_at() {
+        let manager = Manager::new(1.0, 120.0, 512, 0.2).unwrap();
+        assert!(manager.evaluate_at(&[1.0], f64::NAN).is_nan());
+        assert_eq!(manager.evaluate_at(&[], 1.0), 0.0);
+        // 16 samples per period
+        let omega = std::f64::consts::PI / 8.0;
+        let signal: Vec<f64> = (0..256).map(|i| (i as f64 * omega).sin()).collect();
+        let times: Vec<f64> = (0..100).map(|i| 100.0 + i as f64 * 0.37).collect();
+        let values = manager.evaluate_batch(&signal, &times, Padding::Zero);
+        for (t, v) in times.iter().zip(values) {
+            assert!((v - (t * omega).sin()).abs() < 1e-5);
+        }
+        for t in [-3.3, 0.0, 1.5, 254.2, 260.7] {
+            let v = manager.evaluate_at_with(&signal, t, Padding::Wrap);
+            assert!((v - (t * omega).sin()).abs() < 1e-5);
+        }
+        let ones = [1.0; 64];
+        for t in [-10.0, 0.5, 63.0, 70.25] {
+            let v = manager.evaluate_at_with(&ones, t, Padding::Clamp);
+            assert!((v - 1.0).abs() < 1e-5);
+            let v = manager.evaluate_at_with(&ones, t, Padding::Reflect);
+            assert!((v - 1.0).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn test_padding() {
+        assert_eq!(Padding::Zero.index(-1, 4), None);
+        assert_eq!(Padding::Clamp.index(-1, 4), Some(0));
+        assert_eq!(Padding::Clamp.index(5, 4), Some(3));
+        assert_eq!(Padding::Reflect.index(-1, 4), Some(1));
+        assert_eq!(Padding::Reflect.index(4, 4), Some(2));
+        assert_eq!(Padding::Reflect.index(7, 4), Some(1));
+        assert_eq!(Padding::Wrap.index(-1, 4), Some(3));
+        assert_eq!(Padding::Wrap.index(4, 4), Some(0));
+    }
+
+    #[test]
+    fn test_snapshot() {
+        let manager = Manager::new(1.5, 96.0, 128, 0.1).unwrap();
+        let input: Vec<f64> = (0..300).map(|i| (i as f64 * 0.1).sin()).collect();
+        let mut converter = manager.converter();
+        let head: Vec<f64> = converter.process(input[..100].iter().copied()).collect();
+        let snapshot = converter.snapshot();
+        let tail: Vec<f64> = converter.process(input[100..].iter().copied()).collect();
+        let mut restored = manager.converter();
+        restored.restore(&snapshot).unwrap();
+        let again: Vec<f64> = restored.process(input[100..].iter().copied()).collect();
+        assert!(!head.is_empty());
+        assert_eq!(tail, again);
+        let other = Manager::new(2.0, 96.0, 128, 0.1).unwrap();
+        assert_eq!(
+            other.converter().restore(&snapshot),
+            Err(Error::SnapshotMismatch)
+        );
+    }
+
+    #[test]
+    fn test_snapshot_filter() {
+        // the same ratio, order and quantify, but another filter
+        let manager = Manager::with_raw(2.0, 32, 32, 5.0, 0.8).unwrap();
+        let snapshot = manager.converter().snapshot();
+        let beta = Manager::with_raw(2.0, 32, 32, 12.0, 0.8).unwrap();
+        assert_eq!(
+            beta.converter().restore(&snapshot),
+            Err(Error::SnapshotMismatch)
+        );
+        let cutoff = Manager::with_raw(2.0, 32, 32, 5.0, 0.5).unwrap();
+        assert_eq!(
+            cutoff.converter().restore(&snapshot),
+            Err(Error::SnapshotMismatch)
+        );
+        let same = Manager::with_raw(2.0, 32, 32, 5.0, 0.8).unwrap();
+        assert!(same.converter().restore(&snapshot).is_ok());
+    }
+
+    #[test]
+    fn test_config() {
+        let config = "sinc:old_sr=48000,new_sr=44100,atten=96,quan=128,pass_freq=20000";
+        let manager = Manager::from_config(&config.parse().unwrap()).unwrap();
+        let spec = manager.config().to_string();
+        assert!(spec.starts_with("sinc:ratio=147/160,quan=128,order="));
+        let same = Manager::from_config(&spec.parse().unwrap()).unwrap();
+        assert_eq!(same.config(), manager.config());
+        assert_eq!(same.filter, manager.filter);
+        let linear = "linear:ratio=2".parse().unwrap();
+        assert!(Manager::from_config(&linear).is_err());
+    }
+
     #[test]
     fn test_builder() {
         assert!(Manager::builder().build().is_err());
@@ -624,4 +1460,188 @@ mod tests {
             .build();
         assert!(manager.is_ok());
     }
+
+    #[test]
+    fn test_max_table_bytes() {
+        let manager = Manager::new(2.0, 120.0, 512, 0.1).unwrap();
+        assert_eq!(manager.table_bytes(), table_bytes(512, manager.order()));
+        let builder = || {
+            Manager::builder()
+                .ratio(2.0)
+                .attenuation(120)
+                .trans_width(0.1)
+        };
+        let err = builder().quantify(512).max_table_bytes(1 << 16).build();
+        assert!(matches!(err, Err(Error::TableTooLarge { max: 65536, .. })));
+        let manager = builder().max_table_bytes(1 << 16).build().unwrap();
+        assert_eq!(manager.quan, 64);
+        assert!(manager.table_bytes() <= 1 << 16);
+        // not more than the recommended one
+        let manager = builder().max_table_bytes(1 << 30).build().unwrap();
+        assert_eq!(manager.quan, 512);
+        assert!(builder().max_table_bytes(8).build().is_err());
+        assert!(matches!(builder().build(), Err(Error::NotEnoughParam)));
+    }
+
+    #[test]
+    fn test_cost() {
+        let manager = Manager::new(0.5, 96.0, 128, 0.1).unwrap();
+        let cost = manager.cost();
+        assert_eq!(cost.mul_adds, 2.0 * (manager.order() + 1) as f64);
+        assert_eq!(cost.lookups, (manager.order() + 1) as f64);
+        assert_eq!(cost.history, manager.order() as usize + 1);
+        assert!(cost.memory > manager.table_bytes());
+        let builder = || Manager::builder().ratio(2.0).quantify(128);
+        let tw = builder().attenuation(120).trans_width(0.1);
+        let manager = tw.max_cost(100.0).build().unwrap();
+        assert_eq!(manager.order(), 49);
+        assert!(manager.kaiser_beta < calc_kaiser_beta(120.0));
+        assert!((manager.cutoff - 0.95).abs() < 1e-12);
+        let order = builder().attenuation(120).order(100);
+        let manager = order.max_cost(64.0).build().unwrap();
+        assert_eq!(manager.order(), 31);
+        assert_eq!(manager.kaiser_beta, calc_kaiser_beta(120.0));
+        let raw = builder().order(64).kaiser_beta(8.0).cutoff(0.9);
+        assert!(matches!(
+            raw.max_cost(64.0).build(),
+            Err(Error::CostTooHigh {
+                mul_adds: 130.0,
+                max: 64.0
+            })
+        ));
+        let tw = builder().attenuation(120).trans_width(0.01);
+        assert!(matches!(
+            tw.max_cost(8.0).build(),
+            Err(Error::CostTooHigh { .. })
+        ));
+    }
+
+    #[test]
+    fn test_max_latency() {
+        let builder = || Manager::builder().ratio(2.0).quantify(64).attenuation(96);
+        let manager = builder().max_latency_samples(32).build().unwrap();
+        assert_eq!(manager.order(), 32);
+        assert_eq!(manager.latency(), 32);
+        let expected = calc_trans_width(2.0, 96.0, 32);
+        assert!((manager.trans_width() - expected).abs() < 1e-12);
+        let manager = builder().max_latency_ms(1.0, 48000).build().unwrap();
+        assert!(manager.latency() <= 48);
+        let manager = builder()
+            .order(100)
+            .max_latency_samples(20)
+            .build()
+            .unwrap();
+        assert_eq!(manager.order(), 20);
+        assert!(matches!(
+            builder().max_latency_samples(2).build(),
+            Err(Error::LatencyTooHigh { max: 2, .. })
+        ));
+        assert!(matches!(
+            builder().trans_width(0.05).max_latency_samples(20).build(),
+            Err(Error::LatencyTooHigh { max: 20, .. })
+        ));
+        let manager = Manager::builder()
+            .ratio(0.5)
+            .quantify(64)
+            .attenuation(96)
+            .max_latency_samples(10)
+            .build()
+            .unwrap();
+        assert!(manager.latency() <= 10);
+        assert!(latency_of(manager.ratio, manager.order() + 1) > 10);
+    }
+
+    #[test]
+    fn test_validate() {
+        let builder = || Manager::builder().ratio(2.0).quantify(64);
+        assert!(matches!(
+            Manager::builder().ratio(2.0).validate(),
+            Err(Error::NotEnoughParam)
+        ));
+        assert!(matches!(
+            builder().ratio(100.0).attenuation(96).validate(),
+            Err(Error::UnsupportedRatio(_))
+        ));
+        let warnings = builder().attenuation(96).trans_width(0.1).validate();
+        assert_eq!(warnings.unwrap(), []);
+        let warnings = builder()
+            .order(32)
+            .attenuation(96)
+            .kaiser_beta(8.0)
+            .pass_freq(20000)
+            .sample_rate(44100, 88200)
+            .validate();
+        assert_eq!(
+            warnings.unwrap(),
+            [
+                Warning::Overridden {
+                    name: "sample_rate",
+                    by: "ratio"
+                },
+                Warning::Unused("kaiser_beta"),
+                Warning::Overridden {
+                    name: "pass_freq",
+                    by: "order"
+                },
+            ]
+        );
+        let warnings = builder()
+            .order(32)
+            .kaiser_beta(8.0)
+            .cutoff(0.9)
+            .attenuation(96)
+            .validate();
+        assert_eq!(warnings.unwrap(), [Warning::Unused("attenuation")]);
+        let warnings = Manager::builder()
+            .ratio(2.0)
+            .sample_rate(44100, 48000)
+            .attenuation(96)
+            .quantify(64)
+            .pass_freq(20000)
+            .validate();
+        let warning = warnings.unwrap()[0];
+        assert_eq!(warning.name(), "ratio");
+        assert_eq!(
+            warning.to_string(),
+            "`ratio` is overridden by `sample_rate`"
+        );
+    }
+
+    #[test]
+    fn test_strict() {
+        let builder = || {
+            Manager::builder()
+                .ratio(2.0)
+                .quantify(64)
+                .attenuation(96)
+                .trans_width(0.1)
+        };
+        assert!(builder().order(64).build().is_ok());
+        assert_eq!(
+            builder().order(64).strict(true).build().err(),
+            Some(Error::IgnoredParam("order"))
+        );
+        assert!(builder().strict(true).build().is_ok());
+    }
+
+    #[test]
+    fn test_group_delay() {
+        // the output of a ramp is the ramp delayed by the group delay
+        for ratio in [2.0, 0.75, 1.5] {
+            let manager = Manager::new(ratio, 96.0, 256, 0.1).unwrap();
+            let delay = manager.group_delay();
+            assert_eq!(delay, ratio * manager.input_group_delay());
+            let order_delay = ratio * manager.order() as f64 * 0.5;
+            assert_eq!(manager.latency(), order_delay.round() as usize);
+            for phase in [0.0, 0.3, 0.9] {
+                let mut converter = manager.converter_with_phase(phase);
+                let ramp = (0..400).map(|i| i as f64);
+                let output: Vec<f64> = converter.process(ramp).collect();
+                for (k, s) in output.iter().enumerate().skip(200).take(100) {
+                    let expected = (k as f64 - delay + phase) / ratio;
+                    assert!((s - expected).abs() < 1e-3, "{ratio} {phase} {k}");
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,268 @@
+//! Export and import of sinc filter tables
+//!
+//! A [sinc::Manager] can be written with `export_table` and read back with
+//! `from_table`, the table is not generated again. `export_csv` writes the
+//! table for plotting. These need the `std` feature.
+//!
+//! ## Format
+//!
+//! All the numbers are little-endian.
+//!
+//! | bytes | type    | value                                      |
+//! | ----- | ------- | ------------------------------------------ |
+//! | 8     |         | magic `SSRCTAB1`                           |
+//! | 8     | `i64`   | numerator of the ratio                     |
+//! | 8     | `i64`   | denominator of the ratio                   |
+//! | 4     | `u32`   | quantify                                   |
+//! | 4     | `u32`   | order                                      |
+//! | 8     | `f64`   | kaiser beta                                |
+//! | 8     | `f64`   | cutoff                                     |
+//! | 4     | `u32`   | window, 0 is kaiser                        |
+//! | 4     | `u32`   | number of entries, `order * quan / 2 + 1`  |
+//! | 8     | `u64`   | FNV-1a 64 checksum of the entries in bytes |
+//! | 8 * n | `f64`   | the entries                                |
+//!
+//! The entry `i` is the filter at `i / quan`, the last one is always 0.
+
+use core::fmt;
+
+#[cfg(feature = "std")]
+use alloc::sync::Arc;
+#[cfg(feature = "std")]
+use alloc::vec::Vec;
+#[cfg(feature = "std")]
+use num_rational::Rational64;
+#[cfg(feature = "std")]
+use std::io::{self, Read, Write};
+
+#[cfg(feature = "std")]
+use super::{Error, RatioError};
+#[cfg(any(feature = "std", doc))]
+use crate::sinc;
+
+/// Magic bytes and version of the format.
+#[cfg(feature = "std")]
+const MAGIC: &[u8; 8] = b"SSRCTAB1";
+
+/// The window of the tables, only kaiser for now.
+#[cfg(feature = "std")]
+const WINDOW_KAISER: u32 = 0;
+
+/// Why a table is rejected.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TableError {
+    /// The magic bytes or the version is wrong.
+    BadMagic,
+    /// The window is unknown.
+    UnsupportedWindow(u32),
+    /// The number of entries does not match quantify and order.
+    LengthMismatch,
+    /// The entries are corrupted.
+    ChecksumMismatch,
+}
+
+impl fmt::Display for TableError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BadMagic => write!(f, "not a table or unknown version"),
+            Self::UnsupportedWindow(window) => write!(f, "unsupported window {window}"),
+            Self::LengthMismatch => write!(f, "number of entries mismatch"),
+            Self::ChecksumMismatch => write!(f, "checksum mismatch"),
+        }
+    }
+}
+
+impl core::error::Error for TableError {}
+
+#[cfg(feature = "std")]
+fn checksum(table: &[f64]) -> u64 {
+    let mut hash = 0xcbf29ce484222325u64;
+    for b in table.iter().flat_map(|v| v.to_le_bytes()) {
+        hash ^= b as u64;
+        hash = hash.wrapping_mul(0x100000001b3);
+    }
+    hash
+}
+
+#[cfg(feature = "std")]
+fn invalid(err: Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, err)
+}
+
+#[cfg(feature = "std")]
+fn read_bytes<R: Read, const N: usize>(reader: &mut R) -> io::Result<[u8; N]> {
+    let mut bytes = [0; N];
+    reader.read_exact(&mut bytes)?;
+    Ok(bytes)
+}
+
+#[cfg(feature = "std")]
+impl sinc::Manager {
+    /// Write the parameters and the table in the [format](crate::table#format).
+    pub fn export_table<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writer.write_all(MAGIC)?;
+        writer.write_all(&self.ratio.numer().to_le_bytes())?;
+        writer.write_all(&self.ratio.denom().to_le_bytes())?;
+        writer.write_all(&self.quan.to_le_bytes())?;
+        writer.write_all(&self.order.to_le_bytes())?;
+        writer.write_all(&self.kaiser_beta.to_le_bytes())?;
+        writer.write_all(&self.cutoff.to_le_bytes())?;
+        writer.write_all(&WINDOW_KAISER.to_le_bytes())?;
+        writer.write_all(&(self.filter.len() as u32).to_le_bytes())?;
+        writer.write_all(&checksum(&self.filter).to_le_bytes())?;
+        for v in self.filter.iter() {
+            writer.write_all(&v.to_le_bytes())?;
+        }
+        writer.flush()
+    }
+
+    /// Write the table as CSV with the columns `index,position,coef`.
+    pub fn export_csv<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        writeln!(writer, "index,position,coef")?;
+        for (i, v) in self.filter.iter().enumerate() {
+            writeln!(writer, "{i},{:?},{v:?}", i as f64 / self.quan as f64)?;
+        }
+        writer.flush()
+    }
+
+    /// Read a `Manager` written by [export_table](Self::export_table).
+    ///
+    /// An invalid table is [io::ErrorKind::InvalidData] with an [Error] inside.
+    ///
+    /// ```
+    /// use simple_src::sinc;
+    ///
+    /// let manager = sinc::Manager::new(2.0, 96.0, 128, 0.1).unwrap();
+    /// let mut file = Vec::new();
+    /// manager.export_table(&mut file).unwrap();
+    /// let same = sinc::Manager::from_table(file.as_slice()).unwrap();
+    /// assert_eq!(manager.order(), same.order());
+    /// ```
+    pub fn from_table<R: Read>(mut reader: R) -> io::Result<Self> {
+        let r = &mut reader;
+        if &read_bytes::<_, 8>(r)? != MAGIC {
+            return Err(invalid(Error::InvalidTable(TableError::BadMagic)));
+        }
+        let numer = i64::from_le_bytes(read_bytes(r)?);
+        let denom = i64::from_le_bytes(read_bytes(r)?);
+        let quan = u32::from_le_bytes(read_bytes(r)?);
+        let order = u32::from_le_bytes(read_bytes(r)?);
+        let kaiser_beta = f64::from_le_bytes(read_bytes(r)?);
+        let cutoff = f64::from_le_bytes(read_bytes(r)?);
+        let window = u32::from_le_bytes(read_bytes(r)?);
+        let len = u32::from_le_bytes(read_bytes(r)?);
+        let sum = u64::from_le_bytes(read_bytes(r)?);
+        if window != WINDOW_KAISER {
+            return Err(invalid(Error::InvalidTable(TableError::UnsupportedWindow(
+                window,
+            ))));
+        }
+        if denom == 0 {
+            return Err(invalid(Error::UnsupportedRatio(RatioError::NotFinite)));
+        }
+        if numer <= 0 || denom < 0 {
+            return Err(invalid(Error::UnsupportedRatio(RatioError::NotPositive)));
+        }
+        let ratio = Rational64::new(numer, denom);
+        sinc::check_raw(ratio, quan, order, kaiser_beta, cutoff).map_err(invalid)?;
+        if len as u64 != order as u64 * quan as u64 / 2 + 1 {
+            return Err(invalid(Error::InvalidTable(TableError::LengthMismatch)));
+        }
+        let mut filter = Vec::with_capacity(len as usize);
+        for _ in 0..len {
+            filter.push(f64::from_le_bytes(read_bytes(r)?));
+        }
+        if checksum(&filter) != sum {
+            return Err(invalid(Error::InvalidTable(TableError::ChecksumMismatch)));
+        }
+        Ok(Self::from_parts(
+            ratio,
+            quan,
+            order,
+            kaiser_beta,
+            cutoff,
+            Arc::new(filter),
+        ))
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::Convert;
+
+    fn table_error(result: io::Result<sinc::Manager>) -> TableError {
+        let err = result.err().unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+        match err.get_ref().unwrap().downcast_ref::<Error>() {
+            Some(Error::InvalidTable(reason)) => *reason,
+            _ => panic!("not a table error"),
+        }
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let manager = sinc::Manager::new(0.91875, 96.0, 128, 0.1).unwrap();
+        let mut file = Vec::new();
+        manager.export_table(&mut file).unwrap();
+        assert_eq!(file.len(), 64 + 8 * manager.filter.len());
+        let same = sinc::Manager::from_table(file.as_slice()).unwrap();
+        assert_eq!(same.config(), manager.config());
+        assert_eq!(same.filter, manager.filter);
+        let samples: Vec<f64> = (0..100).map(|i| (i as f64 * 0.3).sin()).collect();
+        let a: Vec<f64> = manager
+            .converter()
+            .process(samples.iter().copied())
+            .collect();
+        let b: Vec<f64> = same.converter().process(samples.iter().copied()).collect();
+        assert_eq!(a, b);
+    }
+
+    #[test]
+    fn test_invalid() {
+        let manager = sinc::Manager::new(2.0, 48.0, 8, 0.1).unwrap();
+        let mut file = Vec::new();
+        manager.export_table(&mut file).unwrap();
+        let mut bad = file.clone();
+        bad[7] = b'2';
+        assert_eq!(
+            table_error(sinc::Manager::from_table(bad.as_slice())),
+            TableError::BadMagic
+        );
+        let mut bad = file.clone();
+        bad[48] = 1;
+        assert_eq!(
+            table_error(sinc::Manager::from_table(bad.as_slice())),
+            TableError::UnsupportedWindow(1)
+        );
+        let mut bad = file.clone();
+        bad[52] += 1;
+        assert_eq!(
+            table_error(sinc::Manager::from_table(bad.as_slice())),
+            TableError::LengthMismatch
+        );
+        let mut bad = file.clone();
+        bad[80] ^= 1;
+        assert_eq!(
+            table_error(sinc::Manager::from_table(bad.as_slice())),
+            TableError::ChecksumMismatch
+        );
+        let err = sinc::Manager::from_table(&file[..file.len() - 1])
+            .err()
+            .unwrap();
+        assert_eq!(err.kind(), io::ErrorKind::UnexpectedEof);
+    }
+
+    #[test]
+    fn test_csv() {
+        let manager = sinc::Manager::with_raw(2.0, 2, 2, 0.0, 1.0).unwrap();
+        let mut csv = Vec::new();
+        manager.export_csv(&mut csv).unwrap();
+        let csv = String::from_utf8(csv).unwrap();
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines.len(), 1 + manager.filter.len());
+        assert_eq!(lines[0], "index,position,coef");
+        assert_eq!(lines[1], "0,0.0,1.0");
+        assert_eq!(lines[3], "2,1.0,0.0");
+    }
+}
@@ -0,0 +1,224 @@
+//! True peak meter
+//!
+//! Measures the inter-sample peak with the method of ITU-R BS.1770 Annex 2,
+//! by 4x oversampling with the [sinc] interpolator. The filter has 49 taps and
+//! 4 phases like the one in Annex 2, so no coefficient interpolation is needed,
+//! but it is a Kaiser window design instead of the reference coefficients. It
+//! is within the tolerance of the meter in BS.1770 and EBU Tech 3341.
+//!
+//! ```
+//! use simple_src::true_peak::Meter;
+//!
+//! let samples: Vec<f64> = (0..4800)
+//!     .map(|i| 0.5 * (i as f64 * std::f64::consts::FRAC_PI_2 + 0.25 * core::f64::consts::PI).sin())
+//!     .collect();
+//! let mut meter = Meter::new(1).unwrap();
+//! meter.process(0, &samples);
+//! assert!((meter.true_peak_dbtp(0) + 6.02).abs() < 0.2);
+//! ```
+
+use num_rational::Rational64;
+
+use alloc::vec::Vec;
+
+use crate::{kaiser, math, sinc, Convert};
+
+use super::{Error, Result};
+
+const ORDER: u32 = 48;
+const ATTEN: f64 = 80.0;
+
+struct Channel {
+    converter: sinc::Converter,
+    peak: f64,
+}
+
+pub struct Meter {
+    manager: sinc::Manager,
+    channels: Vec<Channel>,
+    // the partial frame left by process_interleaved
+    pending: Vec<f64>,
+}
+
+impl Meter {
+    /// Create a `Meter` for `channels` channels, which should not be 0.
+    pub fn new(channels: usize) -> Result<Self> {
+        if channels == 0 {
+            return Err(Error::InvalidParam {
+                name: "channels",
+                value: 0.0,
+            });
+        }
+        let ratio = Rational64::from_integer(4);
+        let beta = kaiser::calc_kaiser_beta(ATTEN);
+        let trans_width = (ATTEN - 8.0) / (2.285 * ORDER as f64 * core::f64::consts::PI);
+        let cutoff = 1.0 - 0.5 * trans_width;
+        let manager = sinc::Manager::with_raw_internal(ratio, 4, ORDER, beta, cutoff)?;
+        let channels = (0..channels)
+            .map(|_| Channel {
+                converter: manager.converter(),
+                peak: 0.0,
+            })
+            .collect();
+        Ok(Self {
+            manager,
+            channels,
+            pending: Vec::new(),
+        })
+    }
+
+    /// Get the number of channels.
+    #[inline]
+    pub fn channels(&self) -> usize {
+        self.channels.len()
+    }
+
+    /// Process a block of one channel, can be called multiple times.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not less than the number of channels.
+    pub fn process(&mut self, channel: usize, samples: &[f64]) {
+        let ch = &mut self.channels[channel];
+        let mut peak = ch.peak;
+        for s in samples {
+            peak = peak.max(math::abs(*s));
+        }
+        for s in ch.converter.process(samples.iter().copied()) {
+            peak = peak.max(math::abs(s));
+        }
+        ch.peak = peak;
+    }
+
+    /// Process a block of interleaved samples of all channels.
+    ///
+    /// The length of the block need not be a multiple of the channels, a
+    /// partial frame at the end is kept until the next call.
+    pub fn process_interleaved(&mut self, mut samples: &[f64]) {
+        let count = self.channels.len();
+        if !self.pending.is_empty() {
+            let need = count - self.pending.len();
+            if samples.len() < need {
+                self.pending.extend_from_slice(samples);
+                return;
+            }
+            let mut frame = core::mem::take(&mut self.pending);
+            frame.extend_from_slice(&samples[..need]);
+            self.process_frames(&frame);
+            frame.clear();
+            self.pending = frame;
+            samples = &samples[need..];
+        }
+        let whole = samples.len() - samples.len() % count;
+        self.process_frames(&samples[..whole]);
+        self.pending.extend_from_slice(&samples[whole..]);
+    }
+
+    fn process_frames(&mut self, samples: &[f64]) {
+        let count = self.channels.len();
+        for channel in 0..count {
+            let ch = &mut self.channels[channel];
+            let iter = samples.iter().skip(channel).step_by(count).copied();
+            let mut peak = ch.peak;
+            for s in iter.clone() {
+                peak = peak.max(math::abs(s));
+            }
+            for s in ch.converter.process(iter) {
+                peak = peak.max(math::abs(s));
+            }
+            ch.peak = peak;
+        }
+    }
+
+    /// Get the linear true peak of a channel since creation or last reset.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not less than the number of channels.
+    #[inline]
+    pub fn true_peak(&self, channel: usize) -> f64 {
+        self.channels[channel].peak
+    }
+
+    /// Get the true peak of a channel in dBTP, negative infinity for silence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `channel` is not less than the number of channels.
+    #[inline]
+    pub fn true_peak_dbtp(&self, channel: usize) -> f64 {
+        20.0 * math::log10(self.true_peak(channel))
+    }
+
+    /// Get the max true peak of all channels in dBTP.
+    #[inline]
+    pub fn max_true_peak_dbtp(&self) -> f64 {
+        let peak = self.channels.iter().map(|ch| ch.peak).fold(0.0, f64::max);
+        20.0 * math::log10(peak)
+    }
+
+    /// Clear the peaks and the history of all channels.
+    pub fn reset(&mut self) {
+        for ch in self.channels.iter_mut() {
+            ch.converter = self.manager.converter();
+            ch.peak = 0.0;
+        }
+        self.pending.clear();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_new() {
+        assert!(Meter::new(0).is_err());
+        assert_eq!(Meter::new(2).unwrap().channels(), 2);
+    }
+
+    #[test]
+    fn test_reset() {
+        let mut meter = Meter::new(1).unwrap();
+        assert_eq!(meter.true_peak_dbtp(0), f64::NEG_INFINITY);
+        meter.process(0, &[0.5, -1.0, 0.25]);
+        assert!(meter.true_peak(0) >= 1.0);
+        meter.reset();
+        assert_eq!(meter.true_peak(0), 0.0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_channel_out_of_range() {
+        let mut meter = Meter::new(2).unwrap();
+        meter.process(2, &[0.5]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn test_peak_out_of_range() {
+        Meter::new(2).unwrap().true_peak_dbtp(2);
+    }
+
+    #[test]
+    fn test_interleaved_partial() {
+        let left: Vec<f64> = (0..1000).map(|i| 0.5 * (i as f64 * 0.7).sin()).collect();
+        let right: Vec<f64> = (0..1000).map(|i| 0.9 * (i as f64 * 1.3).cos()).collect();
+        let mut expected = Meter::new(2).unwrap();
+        expected.process(0, &left);
+        expected.process(1, &right);
+        let samples: Vec<f64> = left
+            .iter()
+            .zip(right.iter())
+            .flat_map(|(l, r)| [*l, *r])
+            .collect();
+        let mut meter = Meter::new(2).unwrap();
+        // odd block lengths split the frames
+        for block in samples.chunks(101) {
+            meter.process_interleaved(block);
+        }
+        meter.process_interleaved(&[]);
+        assert_eq!(meter.true_peak(0), expected.true_peak(0));
+        assert_eq!(meter.true_peak(1), expected.true_peak(1));
+    }
+}
@@ -0,0 +1,47 @@
+use std::f64::consts::TAU;
+
+use simple_src::{fixed, sinc, Convert};
+
+const ONE: f64 = (1u64 << 31) as f64;
+
+// The SNR in dB of the fixed-point output of a -1 dBFS sine of `freq` Hz
+// converted from 44.1kHz to 48kHz, taking the f64 output as the reference.
+fn snr(atten: f64, quan: u32, freq: f64) -> f64 {
+    let manager = sinc::Manager::with_sample_rate(44100, 48000, atten, quan, 20000).unwrap();
+    let omega = TAU * freq / 44100.0;
+    let input: Vec<f64> = (0..44100)
+        .map(|i| 0.891 * (i as f64 * omega).sin())
+        .collect();
+    let mut converter = manager.converter();
+    let mut fixed_converter = fixed::Manager::new(&manager).converter();
+    let output = converter.process(input.iter().copied());
+    let fixed_output = fixed_converter.process(input.iter().map(|s| (s * ONE).round() as i32));
+    let (signal, noise) = output
+        .zip(fixed_output)
+        .skip(2048)
+        .fold((0.0, 0.0), |(signal, noise), (s, f)| {
+            (signal + s * s, noise + (s - f as f64 / ONE).powi(2))
+        });
+    10.0 * (signal / noise).log10()
+}
+
+#[test]
+fn tquality() {
+    let presets = [
+        (48.0, 8),
+        (60.0, 16),
+        (72.0, 32),
+        (84.0, 64),
+        (96.0, 128),
+        (108.0, 256),
+        (120.0, 512),
+        (132.0, 1024),
+        (144.0, 2048),
+    ];
+    for (atten, quan) in presets {
+        for freq in [1000.0, 10000.0] {
+            let snr = snr(atten, quan, freq);
+            assert!(snr > 160.0);
+        }
+    }
+}
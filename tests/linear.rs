@@ -1,12 +1,13 @@
 use simple_src::{linear::Manager, Convert};
 
-fn convert(file_prefix: &str, sr_old: u32, sr_new: u32) {
+fn convert(file_prefix: &str, sr_old: u32, sr_new: u32, anti_alias: bool) {
     let ratio = sr_new as f64 / sr_old as f64;
     let source_file = format!("{file_prefix}_{}k.wav", sr_old / 1000);
     let target_file = format!(
-        "{file_prefix}_{}k_{}k_linear.wav",
+        "{file_prefix}_{}k_{}k_linear{}.wav",
         sr_old / 1000,
-        sr_new / 1000
+        sr_new / 1000,
+        if anti_alias { "_aa" } else { "" }
     );
     let mut reader = hound::WavReader::open(source_file).unwrap();
     let out_duration = (ratio * (reader.duration() as f64)) as usize;
@@ -21,7 +22,12 @@ fn convert(file_prefix: &str, sr_old: u32, sr_new: u32) {
         .samples::<f32>()
         .map(|s| s.unwrap() as f64)
         .chain(std::iter::repeat(0.0));
-    Manager::new(ratio)
+    let manager = if anti_alias {
+        Manager::with_anti_alias(ratio)
+    } else {
+        Manager::new(ratio)
+    };
+    manager
         .unwrap()
         .converter()
         .process(in_iter)
@@ -35,12 +41,23 @@ fn convert(file_prefix: &str, sr_old: u32, sr_new: u32) {
 // cargo test -r --test linear -- --ignored --exact tlinear
 fn tlinear() {
     std::env::set_current_dir("output").unwrap();
-    convert("beep", 44100, 48000);
-    convert("beep", 48000, 44100);
-    convert("sweep", 44100, 48000);
-    convert("sweep", 48000, 44100);
-    convert("sweep", 48000, 96000);
-    convert("sweep", 96000, 48000);
+    convert("beep", 44100, 48000, false);
+    convert("beep", 48000, 44100, false);
+    convert("sweep", 44100, 48000, false);
+    convert("sweep", 48000, 44100, false);
+    convert("sweep", 48000, 96000, false);
+    convert("sweep", 96000, 48000, false);
+}
+
+#[test]
+#[ignore = "generate files"]
+// cargo test -r --test linear -- --ignored --exact tlinear_aa
+fn tlinear_aa() {
+    std::env::set_current_dir("output").unwrap();
+    convert("beep", 48000, 44100, true);
+    convert("sweep", 48000, 44100, true);
+    convert("sweep", 96000, 44100, true);
+    convert("sweep", 96000, 48000, true);
 }
 
 #[test]
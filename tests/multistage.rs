@@ -0,0 +1,53 @@
+use simple_src::{multistage::Manager, Convert};
+
+fn convert(file_prefix: &str, sr_old: u32, sr_new: u32, manager: &Manager, remark: &str) {
+    let ratio = sr_new as f64 / sr_old as f64;
+    let source_file = format!("{file_prefix}_{}k.wav", sr_old / 1000);
+    let target_file = format!(
+        "{file_prefix}_{}k_{}k_m_{remark}.wav",
+        sr_old / 1000,
+        sr_new / 1000
+    );
+    let mut reader = hound::WavReader::open(source_file).unwrap();
+    let out_duration = (ratio * (reader.duration() as f64)) as usize;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: sr_new,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(target_file, spec).unwrap();
+    let in_iter = reader
+        .samples::<f32>()
+        .map(|s| s.unwrap() as f64)
+        .chain(std::iter::repeat(0.0));
+    manager
+        .converter()
+        .process(in_iter)
+        .skip(manager.latency())
+        .take(out_duration)
+        .for_each(|s| writer.write_sample(s as f32).unwrap());
+    writer.finalize().unwrap();
+}
+
+#[test]
+#[ignore = "slow"]
+// cargo test -r --test multistage -- --ignored --exact --show-output ta120_192k_down
+fn ta120_192k_down() {
+    std::env::set_current_dir("output").unwrap();
+    let remark = "a120";
+    let manager = Manager::with_sample_rate(192000, 44100, 120.0, 512, 20000).unwrap();
+    println!(
+        "stages of 192k to 44k {remark} is {}",
+        manager.stage_count()
+    );
+    convert("beep", 192000, 44100, &manager, remark);
+    convert("sweep", 192000, 44100, &manager, remark);
+    let manager = Manager::with_sample_rate(192000, 48000, 120.0, 512, 20000).unwrap();
+    println!(
+        "stages of 192k to 48k {remark} is {}",
+        manager.stage_count()
+    );
+    convert("beep", 192000, 48000, &manager, remark);
+    convert("sweep", 192000, 48000, &manager, remark);
+}
@@ -0,0 +1,117 @@
+use std::f64::consts::TAU;
+
+use simple_src::{hermite::Hermite, lagrange::Lagrange, optimal::Optimal, poly, Convert};
+
+fn convert<K: poly::Kernel<N>, const N: usize>(
+    name: &str,
+    file_prefix: &str,
+    sr_old: u32,
+    sr_new: u32,
+) {
+    let ratio = sr_new as f64 / sr_old as f64;
+    let source_file = format!("{file_prefix}_{}k.wav", sr_old / 1000);
+    let target_file = format!(
+        "{file_prefix}_{}k_{}k_{name}.wav",
+        sr_old / 1000,
+        sr_new / 1000
+    );
+    let mut reader = hound::WavReader::open(source_file).unwrap();
+    let out_duration = (ratio * (reader.duration() as f64)) as usize;
+    let spec = hound::WavSpec {
+        channels: 1,
+        sample_rate: sr_new,
+        bits_per_sample: 32,
+        sample_format: hound::SampleFormat::Float,
+    };
+    let mut writer = hound::WavWriter::create(target_file, spec).unwrap();
+    let in_iter = reader
+        .samples::<f32>()
+        .map(|s| s.unwrap() as f64)
+        .chain(std::iter::repeat(0.0));
+    poly::Manager::<K, N>::new(ratio)
+        .unwrap()
+        .converter()
+        .process(in_iter)
+        .take(out_duration)
+        .for_each(|s| writer.write_sample(s as f32).unwrap());
+    writer.finalize().unwrap();
+}
+
+fn convert_all<K: poly::Kernel<N>, const N: usize>(name: &str) {
+    convert::<K, N>(name, "beep", 44100, 48000);
+    convert::<K, N>(name, "beep", 48000, 44100);
+    convert::<K, N>(name, "sweep", 44100, 48000);
+    convert::<K, N>(name, "sweep", 48000, 44100);
+    convert::<K, N>(name, "sweep", 48000, 96000);
+    convert::<K, N>(name, "sweep", 96000, 48000);
+}
+
+#[test]
+#[ignore = "generate files"]
+// cargo test -r --test poly -- --ignored --exact tpoly
+fn tpoly() {
+    std::env::set_current_dir("output").unwrap();
+    convert_all::<Hermite, 4>("hermite");
+    convert_all::<Lagrange, 6>("lagrange");
+    convert_all::<Optimal, 4>("optimal");
+}
+
+// The SNR in dB of a sine of `freq` Hz converted from 44.1kHz to 48kHz, the
+// gain is matched first so that the droop of the pass band is not counted.
+fn snr<K: poly::Kernel<N>, const N: usize>(freq: f64) -> f64 {
+    let ratio = 48000.0 / 44100.0;
+    let omega = TAU * freq / 44100.0;
+    let samples = (0..44100).map(|i| (i as f64 * omega).sin());
+    let mut converter = poly::Manager::<K, N>::new(ratio).unwrap().converter();
+    let mut output = Vec::new();
+    let mut ideal = Vec::new();
+    for (k, s) in converter.process(samples).enumerate().skip(100).take(40000) {
+        // the output k is at the input time (k + 1) / ratio - N / 2
+        let expected = (((k + 1) as f64 / ratio - (N / 2) as f64) * omega).sin();
+        output.push(s);
+        ideal.push(expected);
+    }
+    let signal: f64 = ideal.iter().map(|e| e * e).sum();
+    let gain = output.iter().zip(&ideal).map(|(s, e)| s * e).sum::<f64>() / signal;
+    let noise: f64 = output
+        .iter()
+        .zip(&ideal)
+        .map(|(s, e)| (s - gain * e).powi(2))
+        .sum();
+    10.0 * (signal * gain * gain / noise).log10()
+}
+
+struct Case {
+    name: &'static str,
+    snr: fn(f64) -> f64,
+    // the minimum SNR at 1kHz, 5kHz and 10kHz
+    min: [f64; 3],
+}
+
+const CASES: [Case; 3] = [
+    Case {
+        name: "hermite",
+        snr: snr::<Hermite, 4>,
+        min: [85.0, 45.0, 25.0],
+    },
+    Case {
+        name: "lagrange",
+        snr: snr::<Lagrange, 6>,
+        min: [140.0, 70.0, 35.0],
+    },
+    Case {
+        name: "optimal",
+        snr: snr::<Optimal, 4>,
+        min: [70.0, 60.0, 55.0],
+    },
+];
+
+#[test]
+fn tquality() {
+    for case in CASES {
+        for (freq, min) in [1000.0, 5000.0, 10000.0].into_iter().zip(case.min) {
+            let snr = (case.snr)(freq);
+            assert!(snr > min, "{} at {freq}Hz is {snr}dB", case.name);
+        }
+    }
+}
@@ -1,5 +1,3 @@
-use std::io::Write;
-
 use simple_src::{sinc, Convert};
 
 struct Src {
@@ -96,25 +94,20 @@ fn impulse(src: &Src, remark: &str) {
     writer.finalize().unwrap();
 }
 
-fn impulse_raw(src: &Src, remark: &str) {
+fn table(src: &Src, remark: &str) {
     let filename = format!(
-        "impulse_{}k_{}k_s_{remark}.f64",
+        "table_{}k_{}k_s_{remark}",
         src.sr_old / 1000,
         src.sr_new / 1000
     );
-    let mut file = std::fs::File::create(filename).unwrap();
-    let count = src.sr_old as usize;
-    let in_iter = (0..count)
-        .enumerate()
-        .map(|(i, _)| if i == count / 2 { 1.0 } else { 0.0 });
-    let mut cvrt = src.manager.converter();
-    cvrt.process(in_iter)
-        .skip(src.manager.latency())
-        .take(src.sr_new as usize)
-        .for_each(|s| {
-            file.write(&s.to_ne_bytes()).unwrap();
-        });
-    file.flush().unwrap();
+    let file = std::fs::File::create(format!("{filename}.bin")).unwrap();
+    src.manager
+        .export_table(std::io::BufWriter::new(file))
+        .unwrap();
+    let file = std::fs::File::create(format!("{filename}.csv")).unwrap();
+    src.manager
+        .export_csv(std::io::BufWriter::new(file))
+        .unwrap();
 }
 
 fn cwd() {
@@ -235,12 +228,12 @@ fn ta156_1() {
     println!("order of 44k to 48k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
     let src = Src::new_by_trans_width(48000, 44100, 156.0, 4096, trans_width);
     println!("order of 48k to 44k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
 }
 
 #[test]
@@ -254,12 +247,12 @@ fn ta168_1() {
     println!("order of 44k to 48k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
     let src = Src::new_by_trans_width(48000, 44100, 168.0, 8192, trans_width);
     println!("order of 48k to 44k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
 }
 
 #[test]
@@ -273,12 +266,12 @@ fn ta150_1() {
     println!("order of 44k to 48k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
     let src = Src::new_by_trans_width(48000, 44100, 150.0, 2048, trans_width);
     println!("order of 48k to 44k {remark} is {}", src.manager.order());
     convert("sweep", &src, remark);
     impulse(&src, remark);
-    impulse_raw(&src, remark);
+    table(&src, remark);
 }
 
 #[test]
@@ -0,0 +1,90 @@
+use std::f64::consts::TAU;
+
+use simple_src::true_peak::Meter;
+
+// The tolerance of the true peak meter in BS.1770 and EBU Tech 3341.
+fn check(measured: f64, expected: f64) {
+    assert!(
+        measured - expected <= 0.2 && expected - measured <= 0.4,
+        "measured {measured} dBTP, expected {expected} dBTP"
+    );
+}
+
+// One second of sine, with a short fade in to avoid the overshoot of the onset.
+fn sine(sample_rate: f64, freq: f64, amplitude: f64, phase: f64) -> Vec<f64> {
+    let omega = TAU * freq / sample_rate;
+    let fade = 480;
+    (0..sample_rate as usize)
+        .map(|i| {
+            let gain = if i < fade {
+                0.5 - 0.5 * (i as f64 * TAU * 0.5 / fade as f64).cos()
+            } else {
+                1.0
+            };
+            gain * amplitude * (i as f64 * omega + phase).sin()
+        })
+        .collect()
+}
+
+fn measure(samples: &[f64]) -> f64 {
+    let mut meter = Meter::new(1).unwrap();
+    for block in samples.chunks(480) {
+        meter.process(0, block);
+    }
+    meter.true_peak_dbtp(0)
+}
+
+fn sample_peak(samples: &[f64]) -> f64 {
+    20.0 * samples.iter().fold(0.0, |p: f64, s| p.max(s.abs())).log10()
+}
+
+#[test]
+fn low_freq() {
+    let samples = sine(48000.0, 997.0, 0.5, 0.0);
+    check(measure(&samples), -6.02);
+}
+
+#[test]
+fn quarter_rate_45_degree() {
+    let samples = sine(48000.0, 12000.0, 0.5, TAU / 8.0);
+    assert!((sample_peak(&samples) + 9.03).abs() < 0.01);
+    check(measure(&samples), -6.02);
+    let samples = sine(48000.0, 12000.0, 1.0, TAU / 8.0);
+    assert!((sample_peak(&samples) + 3.01).abs() < 0.01);
+    check(measure(&samples), 0.0);
+}
+
+#[test]
+fn sixth_rate() {
+    let samples = sine(48000.0, 8000.0, 1.0, 0.0);
+    assert!((sample_peak(&samples) + 1.25).abs() < 0.01);
+    check(measure(&samples), 0.0);
+}
+
+#[test]
+fn high_freq() {
+    for freq in [11025.0, 15000.0, 19000.0] {
+        let samples = sine(48000.0, freq, 0.5, 0.3);
+        check(measure(&samples), -6.02);
+    }
+    let samples = sine(44100.0, 19000.0, 0.5, 0.3);
+    check(measure(&samples), -6.02);
+}
+
+#[test]
+fn interleaved() {
+    let left = sine(48000.0, 12000.0, 0.5, TAU / 8.0);
+    let right = sine(48000.0, 997.0, 0.25, 0.0);
+    let samples: Vec<f64> = left
+        .iter()
+        .zip(right.iter())
+        .flat_map(|(l, r)| [*l, *r])
+        .collect();
+    let mut meter = Meter::new(2).unwrap();
+    for block in samples.chunks(960) {
+        meter.process_interleaved(block);
+    }
+    check(meter.true_peak_dbtp(0), -6.02);
+    check(meter.true_peak_dbtp(1), -12.04);
+    check(meter.max_true_peak_dbtp(), -6.02);
+}